@@ -1,6 +1,10 @@
 //! These are data structures that are used inside multiple API modules.
 use serde::{Deserialize, Serialize};
 
+use crate::profile::lenient_other;
+use crate::unhandled::impl_unhandled_fields;
+use crate::{Integer, Map};
+
 /// `ServiceState` is used in `edge` and `gateway`.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -10,6 +14,8 @@ pub enum ServiceState {
     OutOfService,
     PendingService,
     Quiesced,
+    #[serde(untagged, deserialize_with = "lenient_other")]
+    Other(String),
 }
 
 /// `ActivationState` is used in `edge` and `gateway`.
@@ -21,6 +27,8 @@ pub enum ActivationState {
     Pending,
     Activated,
     ReactivationPending,
+    #[serde(untagged, deserialize_with = "lenient_other")]
+    Other(String),
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -29,6 +37,8 @@ pub enum ActivationState {
 pub enum TcpOrUdp {
     Tcp,
     Udp,
+    #[serde(untagged, deserialize_with = "lenient_other")]
+    Other(String),
 }
 
 /// `BastionState` is used in `enterprise` and `gateway`.
@@ -41,6 +51,8 @@ pub enum BastionState {
     UnstageRequested,
     Staged,
     Unstaged,
+    #[serde(untagged, deserialize_with = "lenient_other")]
+    Other(String),
 }
 
 /// `EndpointPkiMode` is used in `edge`, `enterprise` and `gateway`.
@@ -51,4 +63,55 @@ pub enum EndpointPkiMode {
     CertificateDisabled,
     CertificateOptional,
     CertificateRequired,
+    #[serde(untagged, deserialize_with = "lenient_other")]
+    Other(String),
+}
+
+/// What VCO returns from most methods that change something, e.g. `gateway/deleteGateway`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RowsModifiedConfirmation {
+    /// The ID of the row, from the methods that give it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Integer>,
+    /// How many rows were changed.
+    pub rows: Integer,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
+}
+
+impl_unhandled_fields!(RowsModifiedConfirmation);
+
+#[cfg(test)]
+mod test_common {
+    use super::*;
+    use crate::profile::{with_profile, DeserializeProfile};
+    use crate::unhandled::UnhandledFields;
+    use serde_json::json;
+
+    #[test]
+    fn test_enum_other() {
+        let state: ServiceState = serde_json::from_value(json!("IN_SERVICE")).unwrap();
+        assert_eq!(state, ServiceState::InService);
+        assert!(serde_json::from_value::<ServiceState>(json!("HIBERNATING")).is_err());
+
+        with_profile(DeserializeProfile::Lenient, || {
+            let state: ServiceState = serde_json::from_value(json!("IN_SERVICE")).unwrap();
+            assert_eq!(state, ServiceState::InService);
+            let state: ServiceState = serde_json::from_value(json!("HIBERNATING")).unwrap();
+            assert_eq!(state, ServiceState::Other("HIBERNATING".into()));
+            assert_eq!(serde_json::to_value(&state).unwrap(), json!("HIBERNATING"));
+        });
+    }
+
+    #[test]
+    fn test_rows_modified() {
+        let confirmation: RowsModifiedConfirmation =
+            serde_json::from_value(json!({"rows": 1})).unwrap();
+        assert_eq!((confirmation.id, confirmation.rows), (None, 1));
+        let confirmation: RowsModifiedConfirmation =
+            serde_json::from_value(json!({"id": 7, "rows": 1, "warning": "slow"})).unwrap();
+        assert_eq!(confirmation.id, Some(7));
+        assert_eq!(confirmation.unhandled_paths(), ["warning"]);
+    }
 }
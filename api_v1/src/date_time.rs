@@ -11,7 +11,9 @@ use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 use time::format_description::well_known::Rfc3339;
-use time::{OffsetDateTime, UtcOffset};
+use time::{OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+use crate::profile::is_lenient;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum DateTime {
@@ -24,8 +26,8 @@ pub enum DateTime {
 impl Display for DateTime {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            DateTime::None => write!(f, "{}", "null"),
-            DateTime::Never => write!(f, "{}", "never"),
+            DateTime::None => write!(f, "null"),
+            DateTime::Never => write!(f, "never"),
             DateTime::Stamp(inner) => {
                 write!(f, "{}", DateTime::offset_date_time_to_rfc3339_string(inner))
             }
@@ -88,10 +90,23 @@ impl DateTime {
         match self {
             DateTime::None => Err(DateTimeError::NoRfc3339Equivalent),
             DateTime::Never => Err(DateTimeError::NoRfc3339Equivalent),
-            DateTime::Stamp(inner) => Ok(Self::offset_date_time_to_rfc3339_string(&inner)),
+            DateTime::Stamp(inner) => Ok(Self::offset_date_time_to_rfc3339_string(inner)),
         }
     }
 
+    /// Create a `DateTime` from a string like "2023-06-18 12:00:00", as some VCO releases send,
+    /// taking it to be UTC.
+    fn from_sql_utc(value: &str) -> Result<Self, DateTimeError> {
+        let format =
+            time::format_description::parse("[year]-[month]-[day] [hour]:[minute]:[second]")
+                .expect("Date-time format description is valid");
+        Ok(DateTime::Stamp(
+            PrimitiveDateTime::parse(value, &format)
+                .map_err(|e| DateTimeError::BadDateTimeString(e.to_string()))?
+                .assume_utc(),
+        ))
+    }
+
     // TODO implement from_ymdhms_utc
 
     #[inline]
@@ -149,7 +164,13 @@ impl<'de> Deserialize<'de> for DateTime {
                 match v {
                     "null" => Ok(DateTime::None),
                     "0000-00-00 00:00:00" => Ok(DateTime::Never),
-                    _ => DateTime::from_rfc3339(v).map_err(|e| E::custom(e.to_string())),
+                    "" if is_lenient() => Ok(DateTime::None),
+                    _ => DateTime::from_rfc3339(v)
+                        .or_else(|e| match is_lenient() {
+                            true => DateTime::from_sql_utc(v).map_err(|_| e),
+                            false => Err(e),
+                        })
+                        .map_err(|e| E::custom(e.to_string())),
                 }
             }
 
@@ -239,6 +260,35 @@ mod date_time_tests {
         let date: Result<DateTime, _> = serde_json::from_value(number);
         assert_eq!(date.unwrap().to_rfc3339().unwrap(), "2023-06-11T13:22:29Z");
     }
+
+    /// Test the alternative encodings only accepted when lenient.
+    #[test]
+    fn test_datetime_de_lenient() {
+        use crate::profile::{with_profile, DeserializeProfile};
+
+        for value in [json!(""), json!("2023-06-18 12:00:00")] {
+            assert!(serde_json::from_value::<DateTime>(value).is_err());
+        }
+        with_profile(DeserializeProfile::Lenient, || {
+            let date: DateTime = serde_json::from_value(json!("")).unwrap();
+            assert_eq!(date, DateTime::None);
+            let date: DateTime = serde_json::from_value(json!("2023-06-18 12:00:00")).unwrap();
+            assert_eq!(date.to_rfc3339().unwrap(), "2023-06-18T12:00:00Z");
+            assert!(serde_json::from_value::<DateTime>(json!("yesterday")).is_err());
+        });
+    }
+
+    /// Test that `None` and `Never` survive a round trip.
+    #[test]
+    fn test_datetime_round_trip_none_never() {
+        assert_eq!(serde_json::to_value(DateTime::None).unwrap(), json!("null"));
+        let date: DateTime = serde_json::from_value(json!(null)).unwrap();
+        assert_eq!(date, DateTime::None);
+        for value in [json!("null"), json!("0000-00-00 00:00:00")] {
+            let date: DateTime = serde_json::from_value(value.clone()).unwrap();
+            assert_eq!(serde_json::to_value(date).unwrap(), value);
+        }
+    }
 }
 
 //
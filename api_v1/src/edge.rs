@@ -1,9 +1,13 @@
 use serde::{Deserialize, Serialize};
 
-//use crate::date_time::{Interval, DateTime};
 use crate::common::{ActivationState, EndpointPkiMode, ServiceState};
+use crate::date_time::DateTime;
+use crate::id::{EdgeId, EnterpriseId, SiteId};
+use crate::logical_id::LogicalId;
+use crate::profile::lenient_other;
 use crate::tinyint::TinyInt;
-use crate::Integer;
+use crate::unhandled::impl_unhandled_fields;
+use crate::{Integer, Map};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -17,6 +21,8 @@ pub enum BastionPromotedState {
     PromotionRequested,
     PromotionPending,
     Promoted,
+    #[serde(untagged, deserialize_with = "lenient_other")]
+    Other(String),
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -29,6 +35,8 @@ pub enum EdgeState {
     Disabled,
     Expired,
     Connected,
+    #[serde(untagged, deserialize_with = "lenient_other")]
+    Other(String),
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -42,49 +50,57 @@ pub enum HaState {
     PendingDissociation,
     Ready,
     Failed,
+    #[serde(untagged, deserialize_with = "lenient_other")]
+    Other(String),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct EdgeObject {
     pub activation_key: String,
-    pub activation_key_expires: String,
+    pub activation_key_expires: DateTime,
     pub activation_state: ActivationState,
-    pub activation_time: String,
+    pub activation_time: DateTime,
     pub alerts_enabled: TinyInt,
     pub bastion_state: BastionPromotedState,
     pub build_number: String,
-    pub created: String,
-    pub custom_info: String,
-    pub description: String,
+    pub created: DateTime,
+    pub custom_info: Option<String>,
+    pub description: Option<String>,
     pub device_family: String,
-    pub device_id: String,
-    pub dns_name: String,
+    pub device_id: Option<String>,
+    pub dns_name: Option<String>,
     pub edge_state: EdgeState,
-    pub edge_state_time: String,
+    pub edge_state_time: DateTime,
     pub endpoint_pki_mode: EndpointPkiMode,
-    pub enterprise_id: Integer,
-    pub factory_software_version: String,
-    pub factory_build_number: String,
-    pub ha_last_contact: String,
+    pub enterprise_id: EnterpriseId,
+    pub factory_software_version: Option<String>,
+    pub factory_build_number: Option<String>,
+    pub ha_last_contact: DateTime,
     pub ha_previous_state: HaState,
-    pub ha_serial_number: String,
+    pub ha_serial_number: Option<String>,
     pub ha_state: HaState,
-    pub id: Integer,
+    pub id: EdgeId,
     pub is_live: Integer,
-    pub last_contact: String,
-    // #[serde(with = "serde_logical_id")]
-    pub logical_id: String,
+    /// Only returned by VCO 5.x and later.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_software_version_supported_by_vco: Option<bool>,
+    pub last_contact: DateTime,
+    pub logical_id: LogicalId,
     pub model_number: String,
-    pub modified: String,
+    pub modified: DateTime,
     pub name: String,
     pub operator_alerts_enabled: TinyInt,
-    pub self_mac_address: String,
-    pub serial_number: String,
+    pub self_mac_address: Option<String>,
+    pub serial_number: Option<String>,
     pub service_state: ServiceState,
-    pub service_up_since: String,
-    pub site_id: Integer,
-    pub software_updated: String,
+    pub service_up_since: DateTime,
+    pub site_id: SiteId,
+    pub software_updated: DateTime,
     pub software_version: String,
-    pub system_up_since: String,
+    pub system_up_since: DateTime,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
 }
+
+impl_unhandled_fields!(EdgeObject);
@@ -2,24 +2,26 @@ use serde::{Deserialize, Serialize};
 
 use crate::common::{BastionState, EndpointPkiMode};
 use crate::date_time::DateTime;
+use crate::id::{EnterpriseId, GatewayPoolId, NetworkId};
+use crate::logical_id::LogicalId;
 use crate::tinyint::TinyInt;
-use crate::{Double, Integer};
+use crate::unhandled::impl_unhandled_fields;
+use crate::{Double, Map};
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Enterprise {
-    pub id: Integer,
+    pub id: EnterpriseId,
     pub created: DateTime,
-    pub network_id: Integer,
-    pub gateway_pool_id: Integer,
+    pub network_id: NetworkId,
+    pub gateway_pool_id: GatewayPoolId,
     pub alerts_enabled: TinyInt,
     pub operator_alerts_enabled: TinyInt,
     pub endpoint_pki_mode: EndpointPkiMode,
     pub name: String,
     pub domain: Option<String>,
     pub prefix: Option<String>,
-    // #[serde(with = "serde_logical_id")]
-    pub logical_id: String,
+    pub logical_id: LogicalId,
     pub account_number: String,
     pub description: Option<String>,
     pub contact_name: Option<String>,
@@ -38,4 +40,8 @@ pub struct Enterprise {
     pub locale: String,
     pub modified: DateTime,
     pub bastion_state: BastionState,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
 }
+
+impl_unhandled_fields!(Enterprise);
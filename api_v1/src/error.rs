@@ -1,5 +1,10 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Deserializer, Serialize};
 use thiserror::Error;
 
+use crate::Integer;
+
 #[derive(Error, Debug)]
 #[non_exhaustive]
 pub enum SerdeError {
@@ -9,3 +14,328 @@ pub enum SerdeError {
     #[error("Error serializing DateTime field: \"{0}\"")]
     DateTimeSer(String),
 }
+
+//
+// API ERRORS
+//
+
+/// JSON-RPC 2.0 error code for an unparseable request. VCO uses these codes over REST too.
+pub const PARSE_ERROR: Integer = -32700;
+
+/// JSON-RPC 2.0 error code for a malformed request.
+pub const INVALID_REQUEST: Integer = -32600;
+
+/// JSON-RPC 2.0 error code for a method that doesn't exist.
+pub const METHOD_NOT_FOUND: Integer = -32601;
+
+/// JSON-RPC 2.0 error code for bad parameters; VCO uses it for validation failures.
+pub const INVALID_PARAMS: Integer = -32602;
+
+/// JSON-RPC 2.0 error code for an internal error.
+pub const INTERNAL_ERROR: Integer = -32603;
+
+/// VCO's generic server error code, used for most application-level errors.
+pub const SERVER_ERROR: Integer = -32000;
+
+/// Prefixes of messages that VCO uses when authentication fails or a session is no good.
+const AUTH_MESSAGE_PREFIXES: &[&str] = &["tokenError", "authenticationError", "Unauthorized"];
+
+/// Fragments of messages that VCO uses when it is throttling the caller.
+const RATE_LIMITED_MESSAGE_FRAGMENTS: &[&str] = &["too many requests", "rate limit"];
+
+/// Fragments of messages that VCO uses when an object doesn't exist.
+const NOT_FOUND_MESSAGE_FRAGMENTS: &[&str] = &["not found", "does not exist", "no such"];
+
+/// A coarse classification of `Error`s, for callers that need to branch on them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The credentials, token or session were rejected.
+    Auth,
+    /// The parameters of the call failed validation.
+    Validation,
+    /// The object the call referred to doesn't exist.
+    NotFound,
+    /// The API method doesn't exist on this VCO.
+    MethodNotFound,
+    /// VCO is throttling the caller.
+    RateLimited,
+    /// Anything else.
+    Other,
+}
+
+/// `Error`, `ErrorData` and `ErrorValidationDetails` are used to deserialize errors returned from
+/// the API, e.g.
+/// `{"code": -32602, "message": "...", "data": {"valid": false, "error": [{"path": "name", ...}]}}`.
+///
+/// Only `code` and `message` are required. `data` that isn't shaped like `ErrorData`, such as a
+/// string or an array, is kept as it is in `the_rest`, so it doesn't stop the error being read.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", from = "RawError")]
+pub struct Error {
+    pub code: Integer,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<ErrorData>,
+    #[serde(flatten)]
+    pub the_rest: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An `Error` as it arrives, before its `data` is made sense of.
+#[derive(Deserialize)]
+struct RawError {
+    code: Integer,
+    message: String,
+    #[serde(default)]
+    data: Option<serde_json::Value>,
+    #[serde(flatten)]
+    the_rest: serde_json::Map<String, serde_json::Value>,
+}
+
+impl From<RawError> for Error {
+    fn from(raw: RawError) -> Self {
+        let mut the_rest = raw.the_rest;
+        let data = match raw.data {
+            None | Some(serde_json::Value::Null) => None,
+            Some(data) => match ErrorData::deserialize(&data) {
+                Ok(data) => Some(data),
+                Err(_) => {
+                    the_rest.insert("data".into(), data);
+                    None
+                }
+            },
+        };
+        Self {
+            code: raw.code,
+            message: raw.message,
+            data,
+            the_rest,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid: Option<bool>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub error: Vec<ErrorValidationDetails>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub warn: Vec<ErrorValidationDetails>,
+    #[serde(flatten)]
+    pub the_rest: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorValidationDetails {
+    #[serde(default)]
+    pub code: Option<String>,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+impl Error {
+    /// Classify the error.
+    pub fn kind(&self) -> ErrorKind {
+        let message = self.message.to_lowercase();
+        if AUTH_MESSAGE_PREFIXES
+            .iter()
+            .any(|prefix| message.starts_with(&prefix.to_lowercase()))
+        {
+            ErrorKind::Auth
+        } else if RATE_LIMITED_MESSAGE_FRAGMENTS
+            .iter()
+            .any(|fragment| message.contains(fragment))
+        {
+            ErrorKind::RateLimited
+        } else if self.code == METHOD_NOT_FOUND {
+            ErrorKind::MethodNotFound
+        } else if self.code == INVALID_PARAMS
+            || self
+                .data
+                .as_ref()
+                .is_some_and(|data| data.valid == Some(false) || !data.error.is_empty())
+        {
+            ErrorKind::Validation
+        } else if NOT_FOUND_MESSAGE_FRAGMENTS
+            .iter()
+            .any(|fragment| message.contains(fragment))
+        {
+            ErrorKind::NotFound
+        } else {
+            ErrorKind::Other
+        }
+    }
+
+    /// Was authentication or the session rejected?
+    pub fn is_auth_error(&self) -> bool {
+        self.kind() == ErrorKind::Auth
+    }
+
+    /// Did the call's parameters fail validation?
+    pub fn is_validation_error(&self) -> bool {
+        self.kind() == ErrorKind::Validation
+    }
+
+    /// Did the call refer to something that doesn't exist?
+    pub fn is_not_found(&self) -> bool {
+        self.kind() == ErrorKind::NotFound
+    }
+
+    /// Is VCO throttling the caller?
+    pub fn is_rate_limited(&self) -> bool {
+        self.kind() == ErrorKind::RateLimited
+    }
+
+    /// The per-field validation errors, if any.
+    pub fn validation_errors(&self) -> &[ErrorValidationDetails] {
+        self.data.as_ref().map_or(&[], |data| &data.error)
+    }
+
+    /// The per-field validation warnings, if any.
+    pub fn validation_warnings(&self) -> &[ErrorValidationDetails] {
+        self.data.as_ref().map_or(&[], |data| &data.warn)
+    }
+
+    /// The paths of the fields that failed validation, e.g. `["name", "site.contactEmail"]`.
+    pub fn validation_paths(&self) -> Vec<&str> {
+        self.validation_errors()
+            .iter()
+            .filter_map(|details| details.path.as_deref())
+            .collect()
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)?;
+        for details in self.validation_errors() {
+            match &details.path {
+                Some(path) => write!(f, "; {path}: {}", details.message)?,
+                None => write!(f, "; {}", details.message)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// VCO sometimes sends a single validation object and sometimes a list of them.
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<ErrorValidationDetails>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(ErrorValidationDetails),
+        Many(Vec<ErrorValidationDetails>),
+    }
+
+    Ok(match Option::<OneOrMany>::deserialize(deserializer)? {
+        None => vec![],
+        Some(OneOrMany::One(one)) => vec![one],
+        Some(OneOrMany::Many(many)) => many,
+    })
+}
+
+#[cfg(test)]
+mod test_error {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validation_error() {
+        let error: Error = serde_json::from_value(json!({
+            "code": -32602,
+            "message": "validation failed",
+            "data": {
+                "valid": false,
+                "error": [
+                    {"code": "required", "message": "is required", "path": "name"},
+                    {"message": "bad format", "path": "site.contactEmail"}
+                ]
+            }
+        }))
+        .unwrap();
+        assert!(error.is_validation_error());
+        assert_eq!(error.validation_paths(), vec!["name", "site.contactEmail"]);
+        assert_eq!(
+            error.to_string(),
+            "validation failed (-32602); name: is required; site.contactEmail: bad format"
+        );
+    }
+
+    #[test]
+    fn test_single_validation_object() {
+        let error: Error = serde_json::from_value(json!({
+            "code": -32000,
+            "message": "invalid",
+            "data": {"valid": false, "error": {"message": "too long", "path": "description"}}
+        }))
+        .unwrap();
+        assert_eq!(error.kind(), ErrorKind::Validation);
+        assert_eq!(error.validation_paths(), vec!["description"]);
+    }
+
+    #[test]
+    fn test_kinds() {
+        let error = |code, message: &str| Error {
+            code,
+            message: message.into(),
+            data: None,
+            the_rest: Default::default(),
+        };
+        assert!(error(SERVER_ERROR, "tokenError [expired session cookie]").is_auth_error());
+        assert!(error(SERVER_ERROR, "Gateway 12 does not exist").is_not_found());
+        assert!(error(SERVER_ERROR, "Too many requests, slow down").is_rate_limited());
+        assert_eq!(
+            error(METHOD_NOT_FOUND, "no such method").kind(),
+            ErrorKind::MethodNotFound
+        );
+        assert_eq!(error(INTERNAL_ERROR, "oops").kind(), ErrorKind::Other);
+    }
+
+    #[test]
+    fn test_the_rest_kept() {
+        let error: Error =
+            serde_json::from_value(json!({"code": -32000, "message": "x", "requestId": "abc"}))
+                .unwrap();
+        assert_eq!(error.the_rest.get("requestId"), Some(&json!("abc")));
+    }
+
+    #[test]
+    fn test_odd_data_kept() {
+        for data in [
+            json!("Gateway is in use"),
+            json!(["a", "b"]),
+            json!({"error": 3}),
+        ] {
+            let error: Error = serde_json::from_value(json!({
+                "code": -32000,
+                "message": "failed",
+                "data": data.clone()
+            }))
+            .unwrap();
+            assert_eq!(error.data, None);
+            assert_eq!(error.the_rest.get("data"), Some(&data));
+            assert_eq!(error.to_string(), "failed (-32000)");
+        }
+
+        // A validation entry without a message is still read.
+        let error: Error = serde_json::from_value(json!({
+            "code": -32602,
+            "message": "invalid",
+            "data": {"valid": false, "error": [{"path": "name"}]}
+        }))
+        .unwrap();
+        assert_eq!(error.validation_paths(), vec!["name"]);
+
+        assert!(serde_json::from_value::<Error>(json!({"message": "no code"})).is_err());
+    }
+}
@@ -1,13 +1,21 @@
 //! Gateway (VCG) related data structures.
 
+use std::collections::BTreeSet;
 use std::net::{Ipv4Addr, Ipv6Addr};
 
 // use mac_address::MacAddress;
 use serde::{Deserialize, Serialize};
 
 use crate::date_time::{DateTime, Interval};
+use crate::id::{
+    CertificateId, DataCenterId, EdgeId, EnterpriseId, EnterpriseProxyId, GatewayId, GatewayPoolId,
+    NetworkId, SiteId,
+};
+use crate::logical_id::LogicalId;
 use crate::network_address::Address;
+use crate::profile::lenient_other;
 use crate::tinyint::TinyInt;
+use crate::unhandled::impl_unhandled_fields;
 use crate::{Double, Integer, Map, Number, Set};
 
 use crate::common::{ActivationState, BastionState, EndpointPkiMode, ServiceState, TcpOrUdp};
@@ -45,11 +53,50 @@ impl From<&[GatewayMetric]> for GatewayMetrics {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct GetGatewayStatusMetrics {
-    pub gateway_id: Integer,
+    pub gateway_id: GatewayId,
     pub interval: Interval,
     pub metrics: GatewayMetrics,
 }
 
+/// The result of `metrics/getGatewayStatusMetrics`. Only the metrics asked for are there.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GetGatewayStatusMetricsResult {
+    pub tunnel_count: Option<GatewayStatusMetric>,
+    pub tunnel_count_v6: Option<GatewayStatusMetric>,
+    pub memory_pct: Option<GatewayStatusMetric>,
+    pub flow_count: Option<GatewayStatusMetric>,
+    pub cpu_pct: Option<GatewayStatusMetric>,
+    pub handoff_queue_drops: Option<GatewayStatusMetric>,
+    pub connected_edges: Option<GatewayStatusMetric>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
+}
+
+/// One metric's values over the interval asked for, with VCO's summary of them.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayStatusMetric {
+    pub min: Option<Double>,
+    pub max: Option<Double>,
+    pub average: Option<Double>,
+    pub series: Vec<GatewayStatusMetricSeries>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
+}
+
+/// A run of a metric's values, one every `tick_interval` milliseconds from `start_time`. Ticks
+/// with no value are `None`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayStatusMetricSeries {
+    pub start_time: DateTime,
+    pub tick_interval: Integer,
+    pub data: Vec<Option<Double>>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
+}
+
 #[cfg(test)]
 mod test_get_gateway_status_metrics {
     use super::*;
@@ -68,7 +115,7 @@ mod test_get_gateway_status_metrics {
         };
 
         let ggm = GetGatewayStatusMetrics {
-            gateway_id: 1,
+            gateway_id: GatewayId(1),
             interval: interval.clone(),
             metrics: metrics.clone(),
         };
@@ -82,6 +129,126 @@ mod test_get_gateway_status_metrics {
         assert_eq!(d.metrics, metrics);
         assert_eq!(d.interval, interval);
     }
+
+    #[test]
+    fn test_load_result() {
+        use crate::unhandled::UnhandledFields;
+
+        let src = include_str!("../tests/fixtures/5.2/metrics_getGatewayStatusMetrics.json");
+        let result: GetGatewayStatusMetricsResult = serde_json::from_str(src).unwrap();
+        assert!(result.unhandled_paths().is_empty());
+        let cpu_pct = result.cpu_pct.unwrap();
+        assert_eq!(cpu_pct.max, Some(12.25));
+        assert_eq!(cpu_pct.series[0].tick_interval, 300000);
+        assert_eq!(result.flow_count.unwrap().series[0].data[2], None);
+        assert!(result.tunnel_count_v6.is_some());
+
+        // Metrics this crate doesn't know are kept.
+        let result: GetGatewayStatusMetricsResult =
+            serde_json::from_str(r#"{"bgpSessions": {"min": 1}}"#).unwrap();
+        assert_eq!(result.unhandled_paths(), ["bgpSessions"]);
+    }
+}
+
+//
+// Gateway `with` expansions
+//
+
+/// Extra details `network/getNetworkGateways` can include for each gateway, asked for in its
+/// `with` parameter.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum GatewayExpansion {
+    Site,
+    Roles,
+    Pools,
+    DataCenters,
+    Certificates,
+    Enterprises,
+    HandOffEdges,
+    EnterpriseAssociationCounts,
+}
+
+impl GatewayExpansion {
+    /// Every expansion there is.
+    pub const ALL: &'static [GatewayExpansion] = &[
+        GatewayExpansion::Site,
+        GatewayExpansion::Roles,
+        GatewayExpansion::Pools,
+        GatewayExpansion::DataCenters,
+        GatewayExpansion::Certificates,
+        GatewayExpansion::Enterprises,
+        GatewayExpansion::HandOffEdges,
+        GatewayExpansion::EnterpriseAssociationCounts,
+    ];
+}
+
+/// A set of `GatewayExpansion`s. It's ordered, so a request serializes the same way every time.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct GatewayExpansions(BTreeSet<GatewayExpansion>);
+
+impl GatewayExpansions {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains(&self, expansion: GatewayExpansion) -> bool {
+        self.0.contains(&expansion)
+    }
+}
+
+impl FromIterator<GatewayExpansion> for GatewayExpansions {
+    fn from_iter<T: IntoIterator<Item = GatewayExpansion>>(iter: T) -> Self {
+        GatewayExpansions(BTreeSet::from_iter(iter))
+    }
+}
+
+impl From<&[GatewayExpansion]> for GatewayExpansions {
+    fn from(value: &[GatewayExpansion]) -> Self {
+        value.iter().copied().collect()
+    }
+}
+
+/// The body of `network/getNetworkGateways`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNetworkGateways {
+    #[serde(default, skip_serializing_if = "GatewayExpansions::is_empty")]
+    pub with: GatewayExpansions,
+}
+
+#[cfg(test)]
+mod test_get_network_gateways {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_serialize() {
+        let body = GetNetworkGateways {
+            with: [
+                GatewayExpansion::EnterpriseAssociationCounts,
+                GatewayExpansion::DataCenters,
+                GatewayExpansion::Site,
+                GatewayExpansion::DataCenters,
+            ]
+            .into_iter()
+            .collect(),
+        };
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            json!({"with": ["site", "dataCenters", "enterpriseAssociationCounts"]})
+        );
+        assert_eq!(
+            serde_json::to_value(GetNetworkGateways::default()).unwrap(),
+            json!({})
+        );
+
+        let all = GatewayExpansions::from(GatewayExpansion::ALL);
+        let names = serde_json::to_value(&all).unwrap();
+        assert_eq!(names.as_array().unwrap().len(), GatewayExpansion::ALL.len());
+        assert_eq!(names[6], "handOffEdges");
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -95,16 +262,18 @@ pub enum GatewayState {
     OutOfService,
     Connected,
     Offline,
+    #[serde(untagged, deserialize_with = "lenient_other")]
+    Other(String),
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct GatewayCertificate {
-    pub id: Integer,
+    pub id: CertificateId,
     pub created: DateTime,
     pub csr_id: Integer,
-    pub gateway_id: Integer,
-    pub network_id: Integer,
+    pub gateway_id: GatewayId,
+    pub network_id: NetworkId,
     pub certificate: String,
     pub serial_number: String,
     pub subject_key_id: String,
@@ -112,25 +281,53 @@ pub struct GatewayCertificate {
     pub finger_print_256: String,
     pub valid_from: DateTime,
     pub valid_to: DateTime,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
+}
+
+//
+// Gateway Data Center
+//
+
+/// A data center a gateway is in, from the `dataCenters` expansion.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCenter {
+    pub id: DataCenterId,
+    pub name: String,
+    pub logical_id: LogicalId,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
 }
 
 //
 // Gateway Enterprise
 //
 
+/// How many enterprises, edges and profiles are associated with a gateway, from the
+/// `enterpriseAssociationCounts` expansion.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EnterpriseAssociationCount {
+    pub enterprise_count: Integer,
+    pub edge_count: Integer,
+    pub profile_count: Integer,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct GatewayEnterpriseAssoc {
     #[serde(flatten)]
     pub enterprise: Enterprise,
-    pub enterprise_id: Integer,
+    pub enterprise_id: EnterpriseId,
     pub enterprise_object_id: Option<Integer>,
     pub enterprise_object_name: Option<String>,
     pub enterprise_object_type: Option<String>,
-    pub edge_id: Option<Integer>,
+    pub edge_id: Option<EdgeId>,
     pub edge_name: Option<String>,
-    // #[serde(with = "serde_logical_id")]
-    pub edge_logical_id: Option<String>,
+    pub edge_logical_id: Option<LogicalId>,
     pub gateway_type: GatewayType,
     pub pinned: Integer,
 }
@@ -139,6 +336,7 @@ pub struct GatewayEnterpriseAssoc {
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[non_exhaustive]
 pub enum GatewayType {
+    /// VCO's own `OTHER` type.
     Other,
     Super,
     Datacenter,
@@ -146,16 +344,18 @@ pub enum GatewayType {
     SuperAlt,
     Primary,
     Secondary,
+    /// What other enums call `Other`, a name `GatewayType` already uses for VCO's own `OTHER`.
+    #[serde(untagged, deserialize_with = "lenient_other")]
+    Unrecognized(String),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Site {
-    pub id: Integer,
+    pub id: SiteId,
     pub created: DateTime,
     pub name: Option<String>,
-    // #[serde(with = "serde_logical_id")]
-    pub logical_id: String,
+    pub logical_id: LogicalId,
     pub contact_name: String,
     pub contact_phone: Option<String>,
     pub contact_mobile: Option<String>,
@@ -179,6 +379,61 @@ pub struct Site {
     pub shipping_country: Option<String>,
     pub shipping_postal_code: Option<String>,
     pub modified: DateTime,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
+}
+
+/// The parts of a `Site` that can be set when adding or changing a gateway. Those that are `None`
+/// aren't sent, and VCO assigns the rest.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteAttributes {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contact_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contact_phone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contact_mobile: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contact_email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub street_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub street_address2: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lat: Option<Double>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lon: Option<Double>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shipping_same_as_location: Option<TinyInt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shipping_contact_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shipping_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shipping_address2: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shipping_city: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shipping_state: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shipping_country: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shipping_postal_code: Option<String>,
 }
 
 //
@@ -188,19 +443,20 @@ pub struct Site {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct GatewayPool {
-    pub id: Integer,
-    pub network_id: Integer,
-    pub enterprise_proxy_id: Option<Integer>,
+    pub id: GatewayPoolId,
+    pub network_id: NetworkId,
+    pub enterprise_proxy_id: Option<EnterpriseProxyId>,
     pub created: DateTime,
     pub name: String,
     pub description: Option<String>,
-    // #[serde(with = "serde_logical_id")]
-    pub logical_id: String,
+    pub logical_id: LogicalId,
     pub is_default: TinyInt,
     pub ip_v4_enabled: TinyInt,
     pub ip_v6_enabled: TinyInt,
     pub hand_off_type: GatewayHandoffType,
     pub modified: DateTime,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -209,7 +465,83 @@ pub struct GatewayGatewayPool {
     #[serde(flatten)]
     pub gateway_pool: GatewayPool,
     pub gateway_pool_assoc_id: Integer,
-    pub gateway_id: Integer,
+    pub gateway_id: GatewayId,
+}
+
+/// A gateway in a pool, from the `gateways` expansion of `network/getNetworkGatewayPools`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayPoolGateway {
+    pub id: GatewayId,
+    pub name: String,
+    pub logical_id: LogicalId,
+    pub gateway_pool_assoc_id: Option<Integer>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
+}
+
+/// Data structure returned by `network/getNetworkGatewayPools`, which is an array of these.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkGetNetworkGatewayPoolsResultItem {
+    #[serde(flatten)]
+    pub gateway_pool: GatewayPool,
+    /// Chosen in the `with` parameter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gateways: Option<Vec<GatewayPoolGateway>>,
+}
+
+/// The body of `network/insertNetworkGatewayPool`, which adds a gateway pool to a network.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertNetworkGatewayPool {
+    pub network_id: NetworkId,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hand_off_type: Option<GatewayHandoffType>,
+}
+
+/// The result of `network/insertNetworkGatewayPool`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertNetworkGatewayPoolResult {
+    pub id: GatewayPoolId,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
+}
+
+/// The attributes of a gateway pool that `network/updateNetworkGatewayPoolAttributes` can change.
+/// Those that are `None` are left as they are.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayPoolAttributes {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hand_off_type: Option<GatewayHandoffType>,
+}
+
+/// The body of `network/updateNetworkGatewayPoolAttributes`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateNetworkGatewayPoolAttributes {
+    pub id: GatewayPoolId,
+    #[serde(rename = "_update")]
+    pub update: GatewayPoolAttributes,
+}
+
+/// The body of `network/insertNetworkGatewayPoolGateway`, which adds a gateway to a pool.
+/// `network/deleteNetworkGatewayPoolGateway` takes the `gatewayPoolAssocId` of the membership to
+/// remove instead.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayPoolMembership {
+    pub gateway_pool_id: GatewayPoolId,
+    pub gateway_id: GatewayId,
 }
 
 //
@@ -223,6 +555,8 @@ pub struct UtilizationDetail {
     pub overall: Number,
     pub cpu: Number,
     pub memory: Number,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
 }
 
 //
@@ -238,9 +572,8 @@ pub struct GatewayHandoffDetailSubnet {
     pub cidr_prefix: u8,
     pub encrypt: bool,
     pub hand_off_type: GatewayHandoffDetailSubnetHandoffType,
-    // Support unknown fields
-    // #[serde(flatten)]
-    // unhandled_fields: Map<String, serde_json::Value>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -249,6 +582,8 @@ pub struct GatewayHandoffDetailSubnet {
 pub enum GatewayHandoffDetailSubnetHandoffType {
     Nat,
     Vlan,
+    #[serde(untagged, deserialize_with = "lenient_other")]
+    Other(String),
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -258,6 +593,8 @@ pub enum GatewayHandoffType {
     None,
     Allow,
     Only,
+    #[serde(untagged, deserialize_with = "lenient_other")]
+    Other(String),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -276,9 +613,8 @@ pub struct GatewayHandoffDetailIcmpProbe {
     pub frequency_seconds: Option<Integer>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub threshold: Option<Integer>,
-    // Support unknown fields
-    // #[serde(flatten)]
-    // unhandled_fields: Map<String, serde_json::Value>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -287,9 +623,8 @@ pub struct GatewayHandoffDetailIcmpResponder {
     pub enabled: bool,
     pub ip_address: Address<Ipv4Addr>,
     pub mode: String,
-    // Support unknown fields
-    // #[serde(flatten)]
-    // unhandled_fields: Map<String, serde_json::Value>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -300,9 +635,8 @@ pub struct GatewayHandoffDetail {
     pub subnets: Vec<GatewayHandoffDetailSubnet>,
     pub icmp_probe: GatewayHandoffDetailIcmpProbe,
     pub icmp_responder: GatewayHandoffDetailIcmpResponder,
-    // Support unknown fields
-    // #[serde(flatten)]
-    // unhandled_fields: Map<String, serde_json::Value>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -310,15 +644,21 @@ pub struct GatewayHandoffDetail {
 pub struct GatewayHandoffEdge {
     #[serde(flatten)]
     pub _edge_object: EdgeObject,
-    pub edge_id: Integer,
+    pub edge_id: EdgeId,
     pub is_primary: Integer,
     pub pinned: Integer,
-    // #[serde(with = "serde_logical_id")]
-    pub enterprise_logical_id: String,
+    pub enterprise_logical_id: LogicalId,
     pub enterprise_name: String,
-    // Support unknown fields
-    // #[serde(flatten)]
-    // unhandled_fields: Map<String, serde_json::Value>,
+}
+
+/// An edge connected to a gateway, in its `connectedEdgeList`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectedEdge {
+    /// The edge's VCE ID, a UUID.
+    pub vceid: String,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
 }
 
 //
@@ -335,20 +675,21 @@ pub enum GatewayRoleType {
     OnPremise,
     Cde,
     Cws,
+    #[serde(untagged, deserialize_with = "lenient_other")]
+    Other(String),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct GatewayRole {
-    created: DateTime,
-    gateway_id: Integer,
+    pub created: DateTime,
+    pub gateway_id: GatewayId,
     // TODO what does this property of gatewayRole mean?
     //      "x-alternate-name": "gatewayRoleProperty"
-    gateway_role: GatewayRoleType,
-    required: Integer,
-    // Support unknown fields
-    // #[serde(flatten)]
-    // unhandled_fields: Map<String, serde_json::Value>,
+    pub gateway_role: GatewayRoleType,
+    pub required: Integer,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
 }
 
 //
@@ -367,6 +708,8 @@ pub enum SyslogLocalFacility {
     Local5,
     Local6,
     Local7,
+    #[serde(untagged, deserialize_with = "lenient_other")]
+    Other(String),
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -374,6 +717,8 @@ pub enum SyslogLocalFacility {
 #[non_exhaustive]
 pub enum GatewaySyslogCollectorSeverity {
     Info,
+    #[serde(untagged, deserialize_with = "lenient_other")]
+    Other(String),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -383,9 +728,8 @@ pub struct GatewaySyslogCollectorSettings {
     pub port: Option<u16>,
     pub protocol: Option<TcpOrUdp>,
     pub severity: Option<GatewaySyslogCollectorSeverity>,
-    // Support unknown fields
-    // #[serde(flatten)]
-    // unhandled_fields: Map<String, serde_json::Value>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -394,9 +738,8 @@ pub struct GatewaySyslogSettings {
     pub tag: String,
     pub facility_code: SyslogLocalFacility,
     pub collectors: Vec<GatewaySyslogCollectorSettings>,
-    // Support unknown fields
-    // #[serde(flatten)]
-    // unhandled_fields: Map<String, serde_json::Value>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
 }
 
 //
@@ -409,9 +752,8 @@ pub struct IpsecGatewayDetail {
     pub enabled: bool,
     pub strict_host_check: bool,
     pub strict_host_check_d_n: Option<String>,
-    // Support unknown fields
-    // #[serde(flatten)]
-    // unhandled_fields: Map<String, serde_json::Value>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
 }
 
 /// Data structure returned by doing the `network/getNetworkGateways`, which is an array of these.
@@ -419,16 +761,16 @@ pub struct IpsecGatewayDetail {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct NetworkGetNetworkGatewaysResultItem {
-    pub id: Integer,
+    pub id: GatewayId,
     pub name: String,
     pub description: Option<String>,
     pub dns_name: Option<String>,
     pub created: DateTime,
     /// `logicalId` is like "gateway01234567-89ab-cdef-0123-456789abcdef"
-    pub logical_id: String,
-    pub network_id: Option<Integer>,
-    pub enterprise_proxy_id: Option<Integer>,
-    pub site_id: Integer,
+    pub logical_id: LogicalId,
+    pub network_id: Option<NetworkId>,
+    pub enterprise_proxy_id: Option<EnterpriseProxyId>,
+    pub site_id: SiteId,
     pub software_version: String,
     pub build_number: String,
     // Is `deviceId` sometimes a UUID and sometimes a MAC address?
@@ -456,7 +798,7 @@ pub struct NetworkGetNetworkGatewaysResultItem {
     pub endpoint_pki_mode: EndpointPkiMode,
 
     pub connected_edges: Integer,
-    pub connected_edge_list: Option<Vec<Map<String, serde_json::Value>>>,
+    pub connected_edge_list: Option<Vec<ConnectedEdge>>,
 
     pub hand_off_detail: Option<GatewayHandoffDetail>,
 
@@ -467,6 +809,10 @@ pub struct NetworkGetNetworkGatewaysResultItem {
 
     pub private_ip_address: Option<Address<Ipv4Addr>>,
 
+    /// Only sent by VCO 5.2 and later.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_hand_off_enabled: Option<TinyInt>,
+
     //
     // Chosen in `with` parameter
     //
@@ -474,13 +820,13 @@ pub struct NetworkGetNetworkGatewaysResultItem {
     pub certificates: Option<Vec<GatewayCertificate>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub data_centers: Option<Vec<serde_json::Value>>,
+    pub data_centers: Option<Vec<DataCenter>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub enterprise_associations: Option<Vec<GatewayEnterpriseAssoc>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub enterprise_association_count: Option<serde_json::Value>,
+    pub enterprise_association_count: Option<EnterpriseAssociationCount>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub enterprises: Option<Vec<Enterprise>>,
@@ -505,44 +851,277 @@ pub struct NetworkGetNetworkGatewaysResultItem {
     //
 
     // pub data_center_vpn_states: Option<???>
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
+}
 
-    // Support unhandled fields
+//
+// Gateway provisioning and updates
+//
+
+/// The body of `gateway/gatewayProvision`, which adds a gateway to a network.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayProvision {
+    pub network_id: NetworkId,
+    pub name: String,
+    pub ip_address: Ipv4Addr,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site: Option<SiteAttributes>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gateway_pool_id: Option<GatewayPoolId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hand_off_detail: Option<GatewayHandoffDetail>,
+}
+
+/// The result of `gateway/gatewayProvision`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayProvisionResult {
+    pub id: GatewayId,
+    pub logical_id: LogicalId,
+    /// The key to activate the new gateway with.
+    pub activation_key: String,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
+}
+
+/// The attributes of a gateway that `gateway/updateGatewayAttributes` can change. Those that are
+/// `None` are left as they are.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayAttributes {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip_address: Option<Ipv4Addr>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site: Option<SiteAttributes>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hand_off_detail: Option<GatewayHandoffDetail>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub syslog: Option<GatewaySyslogSettings>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_state: Option<ServiceState>,
+}
+
+/// The body of `gateway/updateGatewayAttributes`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateGatewayAttributes {
+    pub id: GatewayId,
     #[serde(flatten)]
-    unhandled_fields: Map<String, serde_json::Value>,
+    pub attributes: GatewayAttributes,
 }
 
 #[cfg(test)]
-mod test_get_gateways {
+mod test_gateway_pools {
+    use super::*;
+    use crate::unhandled::UnhandledFields;
+    use serde_json::json;
+
+    #[test]
+    fn test_pools() {
+        let pools: Vec<NetworkGetNetworkGatewayPoolsResultItem> = serde_json::from_value(json!([{
+            "id": 2,
+            "networkId": 1,
+            "enterpriseProxyId": null,
+            "created": "2020-01-01T00:00:00.000Z",
+            "name": "Default Pool",
+            "description": "Gateways for all enterprises",
+            "logicalId": "9f8e7d6c-5b4a-4392-8170-6f5e4d3c0002",
+            "isDefault": 1,
+            "ipV4Enabled": 1,
+            "ipV6Enabled": 0,
+            "handOffType": "ALLOW",
+            "modified": "2020-01-01T00:00:00.000Z",
+            "gateways": [{
+                "id": 11,
+                "name": "vcg11-fra",
+                "logicalId": "gateway0a1b2c3d-4e5f-4a6b-8c7d-9e0f1a2b0011",
+                "gatewayPoolAssocId": 57,
+                "gatewayState": "CONNECTED"
+            }]
+        }]))
+        .unwrap();
+        assert_eq!(
+            pools[0].gateway_pool.hand_off_type,
+            GatewayHandoffType::Allow
+        );
+        assert_eq!(pools[0].gateways.as_ref().unwrap()[0].id, GatewayId(11));
+        assert_eq!(pools.unhandled_paths(), ["[].gateways[].gatewayState"]);
+
+        let body = UpdateNetworkGatewayPoolAttributes {
+            id: GatewayPoolId(2),
+            update: GatewayPoolAttributes {
+                name: Some("EU Pool".into()),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            json!({"id": 2, "_update": {"name": "EU Pool"}})
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_update_gateway_attributes {
     use super::*;
-    use std::io::Read;
-
-    fn load_test_data(filename: &str) -> String {
-        // let test_data_path = std::env::var("TESTS_DIR")
-        //     .expect("Need to set env-var for tests dir");
-        let test_data_path = "/home/bjg/Work/vco-py/notebook";
-        let mut buf = String::new();
-        std::fs::File::open(format!("{test_data_path}/{filename}"))
-            .expect(&format!("Couldn't load {test_data_path}"))
-            .read_to_string(&mut buf)
-            .expect("Some sort of read error");
-        buf
+    use serde_json::json;
+
+    #[test]
+    fn test_serialize() {
+        let body = UpdateGatewayAttributes {
+            id: GatewayId(80),
+            attributes: GatewayAttributes {
+                name: Some("vcg01-lon-b".into()),
+                ip_address: Some(Ipv4Addr::new(192, 0, 2, 11)),
+                service_state: Some(ServiceState::Quiesced),
+                site: Some(SiteAttributes {
+                    city: Some("London".into()),
+                    shipping_same_as_location: Some(true.into()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            json!({
+                "id": 80,
+                "name": "vcg01-lon-b",
+                "ipAddress": "192.0.2.11",
+                "serviceState": "QUIESCED",
+                "site": {"city": "London", "shippingSameAsLocation": 1}
+            })
+        );
     }
+}
+
+impl_unhandled_fields!(GatewayCertificate);
+impl_unhandled_fields!(DataCenter);
+impl_unhandled_fields!(EnterpriseAssociationCount);
+impl_unhandled_fields!(ConnectedEdge);
+impl_unhandled_fields!(GatewayEnterpriseAssoc => enterprise {});
+impl_unhandled_fields!(Site);
+impl_unhandled_fields!(GatewayPool);
+impl_unhandled_fields!(GatewayGatewayPool => gateway_pool {});
+impl_unhandled_fields!(GatewayPoolGateway);
+impl_unhandled_fields!(NetworkGetNetworkGatewayPoolsResultItem => gateway_pool { gateways });
+impl_unhandled_fields!(InsertNetworkGatewayPoolResult);
+impl_unhandled_fields!(UtilizationDetail);
+impl_unhandled_fields!(GatewayHandoffDetailSubnet);
+impl_unhandled_fields!(GatewayHandoffDetailIcmpProbe);
+impl_unhandled_fields!(GatewayHandoffDetailIcmpResponder);
+impl_unhandled_fields!(GatewayHandoffDetail {
+    subnets,
+    icmp_probe,
+    icmp_responder
+});
+impl_unhandled_fields!(GatewayHandoffEdge => _edge_object {});
+impl_unhandled_fields!(GatewayRole);
+impl_unhandled_fields!(GatewaySyslogCollectorSettings);
+impl_unhandled_fields!(GatewaySyslogSettings { collectors });
+impl_unhandled_fields!(IpsecGatewayDetail);
+impl_unhandled_fields!(GatewayProvisionResult);
+impl_unhandled_fields!(GatewayStatusMetricSeries);
+impl_unhandled_fields!(GatewayStatusMetric { series });
+impl_unhandled_fields!(GetGatewayStatusMetricsResult {
+    tunnel_count,
+    tunnel_count_v6,
+    memory_pct,
+    flow_count,
+    cpu_pct,
+    handoff_queue_drops,
+    connected_edges,
+});
+impl_unhandled_fields!(NetworkGetNetworkGatewaysResultItem {
+    utilization_detail,
+    connected_edge_list,
+    hand_off_detail,
+    ipsec_gateway_detail,
+    certificates,
+    data_centers,
+    enterprise_association_count,
+    enterprise_associations,
+    enterprises,
+    hand_off_edges,
+    pools,
+    site,
+    roles,
+    syslog,
+});
+
+#[cfg(test)]
+mod test_get_gateways {
+    use super::*;
+    use crate::unhandled::UnhandledFields;
 
     #[test]
     fn test_load_get_gateways() {
-        let src = load_test_data("real-vco-gateway-data.json");
+        let src = include_str!("../tests/fixtures/5.2/network_getNetworkGateways.json");
         let gateways_data: Vec<NetworkGetNetworkGatewaysResultItem> =
-            serde_json::de::from_str(&src).unwrap();
-        println!(
-            "private_ip_address {:?}",
-            gateways_data[0].private_ip_address
+            serde_json::de::from_str(src).unwrap();
+        assert_eq!(gateways_data.len(), 2);
+        assert_eq!(
+            gateways_data[0].logical_id.kind(),
+            crate::logical_id::LogicalIdKind::Gateway
         );
-        println!("ip_address {:?}", gateways_data[0].ip_address);
-        println!("ip_v6_address {:?}", gateways_data[0].ip_v6_address);
-        println!("unhandled_fields {:?}", gateways_data[0].unhandled_fields);
+        assert!(gateways_data[0].private_ip_address.is_some());
+        assert!(gateways_data[0].ip_address.is_some());
+        assert!(matches!(
+            gateways_data[0].activation_time,
+            DateTime::Stamp(_)
+        ));
+
+        // A gateway that was never activated.
+        assert!(gateways_data[1].private_ip_address.is_none());
+        assert_eq!(gateways_data[1].activation_time, DateTime::Never);
+
+        // Fields that aren't modeled end up in `unhandled_fields`.
+        assert!(gateways_data[0]
+            .unhandled_fields
+            .contains_key("dataCenterVpnStates"));
+    }
 
-        for item in gateways_data {
-            println!("{:?}", item.utilization_detail)
+    #[test]
+    fn test_unhandled_paths() {
+        let src = include_str!("../tests/fixtures/5.2/network_getNetworkGateways.with_all.json");
+        let mut json: serde_json::Value = serde_json::de::from_str(src).unwrap();
+        json[0]["handOffDetail"]["icmpProbe"]["newProbeField"] = 1.into();
+        json[0]["handOffEdges"][0]["newEdgeField"] = "x".into();
+        json[0]["enterpriseAssociations"][0]["newAssocField"] = true.into();
+        let gateways_data: Vec<NetworkGetNetworkGatewaysResultItem> =
+            serde_json::from_value(json).unwrap();
+
+        let paths = gateways_data.unhandled_paths();
+        for path in [
+            "[].dataCenterVpnStates",
+            "[].handOffDetail.icmpProbe.newProbeField",
+            "[].handOffEdges[].newEdgeField",
+            "[].enterpriseAssociations[].newAssocField",
+        ] {
+            assert!(paths.iter().any(|p| p == path), "{path} not in {paths:?}");
         }
+        let with_all = &gateways_data[0];
+        assert_eq!(with_all.data_centers.as_ref().unwrap()[0].name, "FRA1");
+        assert_eq!(
+            with_all
+                .enterprise_association_count
+                .as_ref()
+                .unwrap()
+                .edge_count,
+            1
+        );
+        assert_eq!(with_all.connected_edge_list.as_ref().unwrap().len(), 1);
+
+        // Flattened structs don't see each other's fields as unhandled.
+        assert!(!paths.iter().any(|p| p.ends_with(".edgeId")));
+        assert!(!paths.iter().any(|p| p.ends_with(".enterpriseId")));
     }
 }
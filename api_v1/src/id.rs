@@ -0,0 +1,91 @@
+//! Typed IDs for VCO's entities.
+//!
+//! VCO's IDs are all integers, but an edge's ID is no use where a gateway's is wanted. Giving each
+//! kind of entity its own ID type makes the compiler catch mix-ups. They serialize as plain
+//! integers.
+
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Integer;
+
+/// Define an ID type for a kind of entity.
+macro_rules! entity_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[serde(transparent)]
+        pub struct $name(pub Integer);
+
+        impl From<Integer> for $name {
+            fn from(id: Integer) -> Self {
+                $name(id)
+            }
+        }
+
+        impl From<$name> for Integer {
+            fn from(id: $name) -> Integer {
+                id.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+    };
+}
+
+entity_id!(
+    /// The ID of a gateway (VCG).
+    GatewayId
+);
+entity_id!(
+    /// The ID of an edge (VCE).
+    EdgeId
+);
+entity_id!(
+    /// The ID of an enterprise, i.e. a customer.
+    EnterpriseId
+);
+entity_id!(
+    /// The ID of an enterprise proxy, i.e. a partner (MSP).
+    EnterpriseProxyId
+);
+entity_id!(
+    /// The ID of a site, the location details of an edge, gateway or enterprise.
+    SiteId
+);
+entity_id!(
+    /// The ID of a network. There's usually just the one.
+    NetworkId
+);
+entity_id!(
+    /// The ID of a gateway pool.
+    GatewayPoolId
+);
+entity_id!(
+    /// The ID of a gateway's certificate.
+    CertificateId
+);
+entity_id!(
+    /// The ID of a data center.
+    DataCenterId
+);
+
+#[cfg(test)]
+mod test_id {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_id_serde() {
+        let id: GatewayId = serde_json::from_value(json!(80)).unwrap();
+        assert_eq!(id, GatewayId(80));
+        assert_eq!(serde_json::to_value(id).unwrap(), json!(80));
+        assert_eq!(id.to_string(), "80");
+        assert_eq!(Integer::from(id), 80);
+    }
+}
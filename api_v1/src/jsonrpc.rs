@@ -0,0 +1,74 @@
+//! Envelope structures for VCO's JSON-RPC 2.0 API.
+//!
+//! The JSON-RPC API takes the same parameter and result objects as the REST API, but wraps them:
+//! the method name goes in the body rather than the URL, and results and errors come back inside a
+//! response object.
+
+use serde::{Deserialize, Serialize};
+
+/// The only JSON-RPC version VCO speaks.
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// A JSON-RPC request, e.g.
+/// `{"jsonrpc":"2.0","method":"network/getNetworkGateways","params":{},"id":1}`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JsonRpcRequest<P> {
+    pub jsonrpc: String,
+    pub method: String,
+    pub params: P,
+    pub id: u64,
+}
+
+impl<P> JsonRpcRequest<P> {
+    pub fn new(method: &str, params: P, id: u64) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.into(),
+            method: method.into(),
+            params,
+            id,
+        }
+    }
+}
+
+/// A JSON-RPC response. Exactly one of `result` or `error` should be present.
+///
+/// `error` is left as raw JSON so that callers can interpret it as a VCO API error.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JsonRpcResponse {
+    pub jsonrpc: Option<String>,
+    #[serde(default)]
+    pub result: Option<serde_json::Value>,
+    #[serde(default)]
+    pub error: Option<serde_json::Value>,
+    pub id: Option<serde_json::Value>,
+}
+
+#[cfg(test)]
+mod test_jsonrpc {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_request_ser() {
+        let req = JsonRpcRequest::new("network/getNetworkGateways", json!({}), 7);
+        assert_eq!(
+            serde_json::to_value(&req).unwrap(),
+            json!({"jsonrpc": "2.0", "method": "network/getNetworkGateways", "params": {}, "id": 7})
+        );
+    }
+
+    #[test]
+    fn test_response_de() {
+        let resp: JsonRpcResponse =
+            serde_json::from_value(json!({"jsonrpc": "2.0", "result": [1, 2], "id": 7})).unwrap();
+        assert_eq!(resp.result, Some(json!([1, 2])));
+        assert!(resp.error.is_none());
+
+        let resp: JsonRpcResponse = serde_json::from_value(
+            json!({"jsonrpc": "2.0", "error": {"code": -32000, "message": "nope"}, "id": 7}),
+        )
+        .unwrap();
+        assert!(resp.result.is_none());
+        assert!(resp.error.is_some());
+    }
+}
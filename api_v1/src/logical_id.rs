@@ -0,0 +1,194 @@
+//! The `LogicalId` type represents the logical IDs VCO gives its entities.
+//!
+//! A logical ID is a UUID, optionally prefixed with the kind of entity it belongs to, e.g.
+//! "gateway01234567-89ab-cdef-0123-456789abcdef" or just "01234567-89ab-cdef-0123-456789abcdef".
+//! It's kept exactly as VCO sent it, so it goes back to VCO unchanged.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// The length of a hyphenated UUID.
+const UUID_LEN: usize = 36;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LogicalId {
+    /// The whole ID, as VCO sent it.
+    text: String,
+    prefix_len: usize,
+    uuid: Uuid,
+}
+
+/// The kind of entity a `LogicalId`'s prefix says it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LogicalIdKind {
+    /// There's no prefix. Enterprises, sites and pools have IDs like this.
+    Unprefixed,
+    Edge,
+    Enterprise,
+    Gateway,
+    /// A prefix this crate doesn't know.
+    Other(String),
+}
+
+impl LogicalId {
+    /// Make a logical ID from `prefix`, which may be empty, and `uuid`.
+    pub fn new(prefix: &str, uuid: Uuid) -> Result<Self, LogicalIdError> {
+        Self::parse(&format!("{prefix}{}", uuid.hyphenated()))
+    }
+
+    /// Parse a logical ID such as "gateway01234567-89ab-cdef-0123-456789abcdef".
+    pub fn parse(value: &str) -> Result<Self, LogicalIdError> {
+        let prefix_len = value
+            .len()
+            .checked_sub(UUID_LEN)
+            .filter(|&len| value.is_char_boundary(len))
+            .ok_or_else(|| LogicalIdError::BadUuid(value.into()))?;
+        let (prefix, uuid) = value.split_at(prefix_len);
+        if !prefix.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(LogicalIdError::BadPrefix(value.into()));
+        }
+        Ok(Self {
+            text: value.into(),
+            prefix_len,
+            uuid: Uuid::parse_str(uuid).map_err(|_| LogicalIdError::BadUuid(value.into()))?,
+        })
+    }
+
+    /// The UUID, without any prefix.
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    /// The prefix, e.g. "gateway", or "" if there isn't one.
+    pub fn prefix(&self) -> &str {
+        &self.text[..self.prefix_len]
+    }
+
+    /// The kind of entity the prefix says this belongs to.
+    pub fn kind(&self) -> LogicalIdKind {
+        match self.prefix() {
+            "" => LogicalIdKind::Unprefixed,
+            "edge" => LogicalIdKind::Edge,
+            "enterprise" => LogicalIdKind::Enterprise,
+            "gateway" => LogicalIdKind::Gateway,
+            other => LogicalIdKind::Other(other.into()),
+        }
+    }
+
+    /// The whole ID, exactly as VCO sent it.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Display for LogicalId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl FromStr for LogicalId {
+    type Err = LogicalIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&str> for LogicalId {
+    type Error = LogicalIdError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+//
+// SERDE
+//
+
+impl Serialize for LogicalId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.text)
+    }
+}
+
+impl<'de> Deserialize<'de> for LogicalId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        Self::parse(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum LogicalIdError {
+    #[error("Logical ID doesn't end in a UUID: \"{0}\"")]
+    BadUuid(String),
+
+    #[error("Bad prefix for logical ID: \"{0}\"")]
+    BadPrefix(String),
+}
+
+#[cfg(test)]
+mod test_logical_id {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse() {
+        let id = LogicalId::parse("gateway01234567-89ab-cdef-0123-456789abcdef").unwrap();
+        assert_eq!(id.prefix(), "gateway");
+        assert_eq!(id.kind(), LogicalIdKind::Gateway);
+        assert_eq!(
+            id.uuid().to_string(),
+            "01234567-89ab-cdef-0123-456789abcdef"
+        );
+
+        let id = LogicalId::parse("01234567-89ab-cdef-0123-456789abcdef").unwrap();
+        assert_eq!(id.prefix(), "");
+        assert_eq!(id.kind(), LogicalIdKind::Unprefixed);
+
+        let id = LogicalId::parse("profile01234567-89ab-cdef-0123-456789abcdef").unwrap();
+        assert_eq!(id.kind(), LogicalIdKind::Other("profile".into()));
+
+        for bad in [
+            "",
+            "gateway",
+            "gateway01234567-89ab-cdef-0123-456789abcdeg",
+            "gw-01234567-89ab-cdef-0123-456789abcdef",
+            "€01234567-89ab-cdef-0123-456789abcdef",
+        ] {
+            assert!(LogicalId::parse(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        // Upper case hex is kept as it was, even though `Uuid` would write it in lower case.
+        for text in [
+            "edge6c5b4a39-2817-4f6e-8d5c-4b3a29180301",
+            "2F1E0D9C-8B7A-4654-9321-0FEDCBA90012",
+        ] {
+            let id: LogicalId = serde_json::from_value(json!(text)).unwrap();
+            assert_eq!(serde_json::to_value(&id).unwrap(), json!(text));
+            assert_eq!(id.to_string(), text);
+        }
+
+        let uuid = Uuid::parse_str("6c5b4a39-2817-4f6e-8d5c-4b3a29180301").unwrap();
+        let id = LogicalId::new("edge", uuid).unwrap();
+        assert_eq!(id.as_str(), "edge6c5b4a39-2817-4f6e-8d5c-4b3a29180301");
+        assert!(LogicalId::new("edge-", uuid).is_err());
+    }
+}
@@ -5,6 +5,8 @@ use std::marker::PhantomData;
 use std::net::{Ipv4Addr, Ipv6Addr};
 use thiserror::Error;
 
+use crate::profile::is_lenient;
+
 pub const UNSET: &str = "unset";
 
 pub trait NetAddress
@@ -84,7 +86,7 @@ where
     type Error = AddressError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        Ok(T::de(value).map_err(|_| AddressError::InvalidIpV4Addr(value.into()))?)
+        T::de(value).map_err(|_| AddressError::InvalidIpV4Addr(value.into()))
     }
 }
 
@@ -163,13 +165,99 @@ where
             where
                 E: serde::de::Error,
             {
-                Ok(T::de(v).map_err(E::custom)?)
+                match T::de(v) {
+                    Err(_) if is_lenient() => Ok(Address::Unknown),
+                    result => result.map_err(E::custom),
+                }
+            }
+
+            /// Only accepted when lenient.
+            fn visit_unit<E>(self) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                if is_lenient() {
+                    Ok(Address::Undefined)
+                } else {
+                    Err(E::invalid_type(serde::de::Unexpected::Unit, &self))
+                }
+            }
+
+            // The rest are only accepted when lenient, as an address of a type VCO didn't say.
+
+            fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                self.unknown(serde::de::Unexpected::Bool(v))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                self.unknown(serde::de::Unexpected::Signed(v))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                self.unknown(serde::de::Unexpected::Unsigned(v))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                self.unknown(serde::de::Unexpected::Float(v))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                while seq.next_element::<serde::de::IgnoredAny>()?.is_some() {}
+                self.unknown(serde::de::Unexpected::Seq)
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                while map
+                    .next_entry::<serde::de::IgnoredAny, serde::de::IgnoredAny>()?
+                    .is_some()
+                {}
+                self.unknown(serde::de::Unexpected::Map)
+            }
+        }
+
+        impl<T> AddressVisitor<T>
+        where
+            T: NetAddress,
+        {
+            /// `Address::Unknown` when lenient, otherwise an error for the `unexpected` value.
+            fn unknown<E>(self, unexpected: serde::de::Unexpected) -> Result<Address<T>, E>
+            where
+                E: serde::de::Error,
+            {
+                if is_lenient() {
+                    Ok(Address::Unknown)
+                } else {
+                    Err(E::invalid_type(unexpected, &self))
+                }
             }
         }
 
-        deserializer.deserialize_str(AddressVisitor {
+        let visitor = AddressVisitor {
             _phantom: PhantomData,
-        })
+        };
+        if is_lenient() {
+            deserializer.deserialize_any(visitor)
+        } else {
+            deserializer.deserialize_str(visitor)
+        }
     }
 }
 
@@ -185,3 +273,46 @@ pub enum AddressError {
     #[error("Invalid value for MAC address: \"{0}\"")]
     InvalidMacAddress(String),
 }
+
+#[cfg(test)]
+mod test_address {
+    use super::*;
+    use crate::profile::{with_profile, DeserializeProfile};
+    use serde_json::json;
+
+    #[test]
+    fn test_address_de() {
+        let address: Address<Ipv4Addr> = serde_json::from_value(json!("192.0.2.1")).unwrap();
+        assert_eq!(address, Address::Some(Ipv4Addr::new(192, 0, 2, 1)));
+        let address: Address<Ipv4Addr> = serde_json::from_value(json!("")).unwrap();
+        assert_eq!(address, Address::Undefined);
+        assert!(serde_json::from_value::<Address<Ipv4Addr>>(json!(null)).is_err());
+        assert!(serde_json::from_value::<Address<Ipv4Addr>>(json!("192.0.2")).is_err());
+    }
+
+    #[test]
+    fn test_address_de_lenient() {
+        with_profile(DeserializeProfile::Lenient, || {
+            let address: Address<Ipv4Addr> = serde_json::from_value(json!(null)).unwrap();
+            assert_eq!(address, Address::Undefined);
+            let address: Address<Ipv6Addr> = serde_json::from_value(json!("192.0.2.1")).unwrap();
+            assert_eq!(address, Address::Unknown);
+            let address: Address<Ipv4Addr> = serde_json::from_value(json!("192.0.2.1")).unwrap();
+            assert_eq!(address, Address::Some(Ipv4Addr::new(192, 0, 2, 1)));
+            for odd in [
+                json!(3232235521u32),
+                json!(-1),
+                json!(1.5),
+                json!(true),
+                json!(["a"]),
+            ] {
+                let address: Address<Ipv4Addr> = serde_json::from_value(odd).unwrap();
+                assert_eq!(address, Address::Unknown);
+            }
+            let address: Address<MacAddress> =
+                serde_json::from_str(r#"{"mac": "00:11:22:33:44:55"}"#).unwrap();
+            assert_eq!(address, Address::Unknown);
+        });
+        assert!(serde_json::from_value::<Address<Ipv4Addr>>(json!(1)).is_err());
+    }
+}
@@ -0,0 +1,91 @@
+//! How forgiving deserialization is of the ways VCO's encodings drift between releases.
+//!
+//! Fields flip between `tinyint` and `boolean`, date-times come back as empty strings, and new
+//! enum values appear. The `Strict` profile rejects all of that, which is what tests want so that
+//! drift gets noticed. The `Lenient` profile accepts it, so that one odd field doesn't lose a whole
+//! response:
+//!
+//! - `TinyInt` accepts `true`/`false` and `"0"`/`"1"`/`"true"`/`"false"`;
+//! - `DateTime` accepts `""` as `DateTime::None`, and `"2023-06-18 12:00:00"` as UTC;
+//! - `Address<T>` accepts `null` as `Address::Undefined`, and anything it can't parse as
+//!   `Address::Unknown`;
+//! - enums accept values they don't know as their `Other(String)` variant.
+//!
+//! The profile applies to everything deserialized inside `with_profile` on the same thread.
+
+use std::cell::Cell;
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+
+/// How forgiving deserialization is. See the module docs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DeserializeProfile {
+    /// Accept only the encodings this crate models.
+    #[default]
+    Strict,
+    /// Accept alternative encodings seen across VCO releases too.
+    Lenient,
+}
+
+thread_local! {
+    static PROFILE: Cell<DeserializeProfile> = const { Cell::new(DeserializeProfile::Strict) };
+}
+
+/// Run `f`, deserializing with `profile` on this thread until it returns.
+pub fn with_profile<R>(profile: DeserializeProfile, f: impl FnOnce() -> R) -> R {
+    /// Puts the previous profile back, even if `f` panics.
+    struct Restore(DeserializeProfile);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            PROFILE.with(|current| current.set(self.0));
+        }
+    }
+
+    let _restore = Restore(PROFILE.with(|current| current.replace(profile)));
+    f()
+}
+
+/// The profile deserialization is using on this thread.
+pub fn current_profile() -> DeserializeProfile {
+    PROFILE.with(Cell::get)
+}
+
+pub(crate) fn is_lenient() -> bool {
+    current_profile() == DeserializeProfile::Lenient
+}
+
+/// Deserialize the `Other(String)` variant of an enum, which holds a value this crate doesn't
+/// know. Only the lenient profile accepts one; the strict profile fails as if there were no such
+/// variant.
+pub(crate) fn lenient_other<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    if is_lenient() {
+        Ok(value)
+    } else {
+        Err(D::Error::custom(format!("unknown variant `{value}`")))
+    }
+}
+
+#[cfg(test)]
+mod test_profile {
+    use super::*;
+
+    #[test]
+    fn test_with_profile() {
+        assert_eq!(current_profile(), DeserializeProfile::Strict);
+        let inner = with_profile(DeserializeProfile::Lenient, || {
+            let nested = with_profile(DeserializeProfile::Strict, current_profile);
+            (nested, current_profile())
+        });
+        assert_eq!(
+            inner,
+            (DeserializeProfile::Strict, DeserializeProfile::Lenient)
+        );
+        assert_eq!(current_profile(), DeserializeProfile::Strict);
+    }
+}
@@ -1,8 +1,10 @@
 use serde::{Deserialize, Serialize};
 
 use crate::date_time::DateTime;
+use crate::profile::lenient_other;
 use crate::tinyint::TinyInt;
-use crate::Integer;
+use crate::unhandled::impl_unhandled_fields;
+use crate::{Integer, Map};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -14,6 +16,8 @@ pub enum PropertyDataType {
     Json,
     Date,
     Datetime,
+    #[serde(untagged, deserialize_with = "lenient_other")]
+    Other(String),
 }
 
 /// This is the basic SystemProperty that can be sent to update or insert.
@@ -28,6 +32,8 @@ pub struct SystemProperty {
     pub is_password: TinyInt,
     pub data_type: PropertyDataType,
     pub description: Option<String>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
 }
 
 /// This is the system property as returned by VCO, complete with create and modify datetimes.
@@ -39,3 +45,6 @@ pub struct GetSystemPropertiesResultItem {
     pub created: DateTime,
     pub modified: DateTime,
 }
+
+impl_unhandled_fields!(SystemProperty);
+impl_unhandled_fields!(GetSystemPropertiesResultItem => property {});
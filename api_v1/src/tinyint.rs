@@ -3,6 +3,8 @@ use std::fmt::{Display, Formatter};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 
+use crate::profile::is_lenient;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TinyInt(pub bool);
 
@@ -64,7 +66,7 @@ impl<'de> Deserialize<'de> for TinyInt {
             type Value = TinyInt;
 
             fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
-                formatter.write_str("invalid value for TinyInt")
+                formatter.write_str("0 or 1")
             }
 
             fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
@@ -74,6 +76,30 @@ impl<'de> Deserialize<'de> for TinyInt {
                 let byte: u8 = v.try_into().map_err(|e| E::custom(e))?;
                 TinyInt::try_from(byte).map_err(|e| E::custom(e))
             }
+
+            /// Some VCO releases send booleans instead. Only accepted when lenient.
+            fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                if is_lenient() {
+                    Ok(TinyInt(v))
+                } else {
+                    Err(E::invalid_type(serde::de::Unexpected::Bool(v), &self))
+                }
+            }
+
+            /// Some VCO releases send strings instead. Only accepted when lenient.
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match v {
+                    "0" | "false" if is_lenient() => Ok(TinyInt(false)),
+                    "1" | "true" if is_lenient() => Ok(TinyInt(true)),
+                    _ => Err(E::invalid_value(serde::de::Unexpected::Str(v), &self)),
+                }
+            }
         }
 
         deserializer.deserialize_any(TinyIntVisitor)
@@ -86,3 +112,40 @@ pub enum TinyIntError {
     #[error("Invalid value for TinyInt: \"{0}\"")]
     InvalidValue(u8),
 }
+
+#[cfg(test)]
+mod test_tinyint {
+    use super::*;
+    use crate::profile::{with_profile, DeserializeProfile};
+    use serde_json::json;
+
+    #[test]
+    fn test_tinyint_strict() {
+        assert_eq!(
+            serde_json::from_value::<TinyInt>(json!(1)).unwrap(),
+            TinyInt(true)
+        );
+        for value in [json!(2), json!(true), json!("1"), json!(null)] {
+            assert!(serde_json::from_value::<TinyInt>(value).is_err());
+        }
+    }
+
+    #[test]
+    fn test_tinyint_lenient() {
+        with_profile(DeserializeProfile::Lenient, || {
+            for (value, expected) in [
+                (json!(0), false),
+                (json!(true), true),
+                (json!(false), false),
+                (json!("1"), true),
+                (json!("false"), false),
+            ] {
+                assert_eq!(
+                    serde_json::from_value::<TinyInt>(value).unwrap(),
+                    TinyInt(expected)
+                );
+            }
+            assert!(serde_json::from_value::<TinyInt>(json!("yes")).is_err());
+        });
+    }
+}
@@ -0,0 +1,150 @@
+//! Finding the fields of VCO's responses that this crate doesn't model.
+//!
+//! Every response struct keeps fields it doesn't know about in a public `unhandled_fields` map,
+//! rather than dropping them, so nothing is lost when VCO adds fields in a new release. The
+//! `UnhandledFields` trait walks a response, including the structs nested in it, and reports
+//! where those fields were found.
+
+use serde_json::Value;
+
+/// A response, or part of one, that may hold fields this crate doesn't model.
+pub trait UnhandledFields {
+    /// Call `visit` with the path and value of every unhandled field in this and nested structs.
+    ///
+    /// Paths are made of the JSON field names, e.g. `handOffDetail.icmpProbe.newField`, with
+    /// `[]` standing for any element of an array, and start with `path`.
+    fn visit_unhandled(&self, path: &str, visit: &mut dyn FnMut(&str, &Value));
+
+    /// The paths of every unhandled field in this and nested structs.
+    fn unhandled_paths(&self) -> Vec<String> {
+        let mut paths = vec![];
+        self.visit_unhandled("", &mut |path, _| paths.push(path.to_string()));
+        paths
+    }
+}
+
+/// An empty response, which has nothing to report.
+impl UnhandledFields for () {
+    fn visit_unhandled(&self, _path: &str, _visit: &mut dyn FnMut(&str, &Value)) {}
+}
+
+impl<T: UnhandledFields> UnhandledFields for Option<T> {
+    fn visit_unhandled(&self, path: &str, visit: &mut dyn FnMut(&str, &Value)) {
+        if let Some(inner) = self {
+            inner.visit_unhandled(path, visit);
+        }
+    }
+}
+
+impl<T: UnhandledFields> UnhandledFields for Vec<T> {
+    fn visit_unhandled(&self, path: &str, visit: &mut dyn FnMut(&str, &Value)) {
+        let path = format!("{path}[]");
+        for item in self {
+            item.visit_unhandled(&path, visit);
+        }
+    }
+}
+
+/// `path` extended with the field `name`, for implementations of `UnhandledFields`.
+pub fn join_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{path}.{name}")
+    }
+}
+
+/// The name `#[serde(rename_all = "camelCase")]` gives the Rust field `name`. Used by
+/// `impl_unhandled_fields!`.
+#[doc(hidden)]
+pub fn camel_case(name: &str) -> String {
+    let mut camel = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.trim_start_matches('_').chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            camel.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+/// Implement `UnhandledFields` for a response struct.
+///
+/// - `impl_unhandled_fields!(Type)` for a struct with its own `unhandled_fields` map.
+/// - `impl_unhandled_fields!(Type { nested, ... })` to also visit the named fields, which must
+///   implement `UnhandledFields` themselves.
+/// - `impl_unhandled_fields!(Type => inner { nested, ... })` for a struct that `#[serde(flatten)]`s
+///   `inner`. Its unhandled fields end up in `inner`'s map, so it has no map of its own.
+///
+/// It's exported for `api_v2`'s response structs too.
+#[macro_export]
+macro_rules! impl_unhandled_fields {
+    ($type:ty) => {
+        $crate::unhandled::impl_unhandled_fields!($type {});
+    };
+    ($type:ty { $($nested:ident),* $(,)? }) => {
+        impl $crate::unhandled::UnhandledFields for $type {
+            fn visit_unhandled(
+                &self,
+                path: &str,
+                visit: &mut dyn FnMut(&str, &serde_json::Value),
+            ) {
+                for (name, value) in &self.unhandled_fields {
+                    visit(&$crate::unhandled::join_path(path, name), value);
+                }
+                $(
+                    $crate::unhandled::UnhandledFields::visit_unhandled(
+                        &self.$nested,
+                        &$crate::unhandled::join_path(
+                            path,
+                            &$crate::unhandled::camel_case(stringify!($nested)),
+                        ),
+                        visit,
+                    );
+                )*
+            }
+        }
+    };
+    ($type:ty => $inner:ident { $($nested:ident),* $(,)? }) => {
+        impl $crate::unhandled::UnhandledFields for $type {
+            fn visit_unhandled(
+                &self,
+                path: &str,
+                visit: &mut dyn FnMut(&str, &serde_json::Value),
+            ) {
+                $crate::unhandled::UnhandledFields::visit_unhandled(&self.$inner, path, visit);
+                $(
+                    $crate::unhandled::UnhandledFields::visit_unhandled(
+                        &self.$nested,
+                        &$crate::unhandled::join_path(
+                            path,
+                            &$crate::unhandled::camel_case(stringify!($nested)),
+                        ),
+                        visit,
+                    );
+                )*
+            }
+        }
+    };
+}
+
+pub use crate::impl_unhandled_fields;
+
+#[cfg(test)]
+mod test_unhandled {
+    use super::*;
+
+    #[test]
+    fn test_camel_case() {
+        assert_eq!(camel_case("hand_off_detail"), "handOffDetail");
+        assert_eq!(camel_case("ip_v6_address"), "ipV6Address");
+        assert_eq!(camel_case("strict_host_check_d_n"), "strictHostCheckDN");
+        assert_eq!(camel_case("_edge_object"), "edgeObject");
+        assert_eq!(camel_case("site"), "site");
+    }
+}
@@ -0,0 +1,59 @@
+//! Structs describing VCO users.
+
+use serde::{Deserialize, Serialize};
+
+use crate::id::{EnterpriseId, EnterpriseProxyId, NetworkId};
+use crate::tinyint::TinyInt;
+use crate::unhandled::impl_unhandled_fields;
+use crate::{Integer, Map};
+
+/// A user as returned by `operatorUser/getOperatorUser`.
+///
+/// With no `id` or `username` in the request, VCO returns the user making the call, which makes
+/// it a cheap way of checking who a session or token belongs to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    pub id: Integer,
+    pub username: String,
+    /// e.g. "OPERATOR", "MSP" or "ENTERPRISE". Kept as a string so that a new type can't break
+    /// login.
+    pub user_type: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub email: Option<String>,
+    pub role_id: Option<Integer>,
+    pub role_name: Option<String>,
+    pub network_id: Option<NetworkId>,
+    pub enterprise_id: Option<EnterpriseId>,
+    pub enterprise_proxy_id: Option<EnterpriseProxyId>,
+    pub is_active: Option<TinyInt>,
+    pub is_locked: Option<TinyInt>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
+}
+
+impl_unhandled_fields!(User);
+
+#[cfg(test)]
+mod test_user {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_user_de() {
+        let user: User = serde_json::from_value(json!({
+            "id": 3,
+            "username": "alice@example.com",
+            "userType": "OPERATOR",
+            "roleId": 1,
+            "roleName": "Superuser",
+            "isActive": 1,
+            "networkId": 1
+        }))
+        .unwrap();
+        assert_eq!(user.role_name.as_deref(), Some("Superuser"));
+        assert_eq!(user.is_active, Some(TinyInt(true)));
+        assert_eq!(user.enterprise_id, None);
+    }
+}
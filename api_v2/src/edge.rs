@@ -0,0 +1,49 @@
+//! `enterprises/{logicalId}/edges`: an enterprise's edges (VCE).
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use api_v1::id::{EdgeId, EnterpriseId, SiteId};
+use api_v1::logical_id::LogicalId;
+use api_v1::unhandled::impl_unhandled_fields;
+
+use crate::Map;
+
+/// An item of `GET enterprises/{logicalId}/edges`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Edge {
+    pub id: EdgeId,
+    pub logical_id: LogicalId,
+    pub name: String,
+    pub description: Option<String>,
+    pub enterprise_id: EnterpriseId,
+    pub site_id: SiteId,
+    pub edge_state: String,
+    pub activation_state: String,
+    pub service_state: String,
+    pub model_number: String,
+    pub device_family: String,
+    pub serial_number: Option<String>,
+    pub self_mac_address: Option<String>,
+    pub software_version: String,
+    pub build_number: String,
+    pub is_live: bool,
+    pub alerts_enabled: bool,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub created: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub modified: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub activation_time: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub last_contact: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub service_up_since: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub system_up_since: Option<OffsetDateTime>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
+}
+
+impl_unhandled_fields!(Edge);
@@ -0,0 +1,40 @@
+//! `enterprises`: the customers on the orchestrator.
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use api_v1::id::{EnterpriseId, GatewayPoolId, NetworkId};
+use api_v1::logical_id::LogicalId;
+use api_v1::unhandled::impl_unhandled_fields;
+
+use crate::Map;
+
+/// An item of `GET enterprises`, or the result of `GET enterprises/{logicalId}`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Enterprise {
+    pub id: EnterpriseId,
+    pub logical_id: LogicalId,
+    pub name: String,
+    pub description: Option<String>,
+    pub domain: Option<String>,
+    pub account_number: String,
+    pub network_id: NetworkId,
+    pub gateway_pool_id: GatewayPoolId,
+    pub alerts_enabled: bool,
+    pub operator_alerts_enabled: bool,
+    pub contact_name: Option<String>,
+    pub contact_email: Option<String>,
+    pub city: Option<String>,
+    pub country: Option<String>,
+    pub timezone: String,
+    pub locale: String,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub created: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub modified: Option<OffsetDateTime>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
+}
+
+impl_unhandled_fields!(Enterprise);
@@ -0,0 +1,101 @@
+//! `gateways`: the orchestrator's gateways (VCG). Only operators can see them.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use api_v1::id::{EnterpriseProxyId, GatewayId, NetworkId, SiteId};
+use api_v1::logical_id::LogicalId;
+use api_v1::unhandled::impl_unhandled_fields;
+
+use crate::Map;
+
+/// An item of `GET gateways`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Gateway {
+    pub id: GatewayId,
+    pub logical_id: LogicalId,
+    pub name: String,
+    pub description: Option<String>,
+    pub dns_name: Option<String>,
+    pub network_id: Option<NetworkId>,
+    pub enterprise_proxy_id: Option<EnterpriseProxyId>,
+    pub site_id: SiteId,
+    pub software_version: String,
+    pub build_number: String,
+    pub device_id: Option<String>,
+    pub ip_address: Option<Ipv4Addr>,
+    pub ip_v6_address: Option<Ipv6Addr>,
+    pub private_ip_address: Option<Ipv4Addr>,
+    pub gateway_state: String,
+    pub activation_state: String,
+    pub service_state: String,
+    pub utilization: f32,
+    pub connected_edges: i32,
+    pub is_load_balanced: bool,
+    pub alerts_enabled: Option<bool>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub created: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub modified: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub activation_time: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub last_contact: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub service_up_since: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub system_up_since: Option<OffsetDateTime>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, serde_json::Value>,
+}
+
+impl_unhandled_fields!(Gateway);
+
+#[cfg(test)]
+mod test_gateway {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize() {
+        let gateway: Gateway = serde_json::from_value(json!({
+            "id": 81,
+            "logicalId": "gateway5a6b7c8d-9e0f-4a1b-8c2d-3e4f5a6b7c8d",
+            "name": "vcg02-nyc",
+            "description": null,
+            "dnsName": null,
+            "networkId": 1,
+            "enterpriseProxyId": 3,
+            "siteId": 97,
+            "softwareVersion": "5.2.0",
+            "buildNumber": "R520-20230714-GA",
+            "deviceId": null,
+            "ipAddress": "198.51.100.20",
+            "ipV6Address": null,
+            "privateIpAddress": null,
+            "gatewayState": "NOT_CONNECTED",
+            "activationState": "PENDING",
+            "serviceState": "IN_SERVICE",
+            "utilization": 0.0,
+            "connectedEdges": 0,
+            "isLoadBalanced": false,
+            "alertsEnabled": null,
+            "created": "2022-08-01T09:00:00.000Z",
+            "modified": "2023-06-18T11:00:00.000Z",
+            "lastContact": null,
+            "roles": []
+        }))
+        .unwrap();
+        assert_eq!(gateway.id, GatewayId(81));
+        assert_eq!(gateway.ip_address, Some(Ipv4Addr::new(198, 51, 100, 20)));
+        assert!(gateway.created.is_some());
+        assert_eq!(gateway.activation_time, None);
+        assert_eq!(
+            gateway.unhandled_fields.keys().collect::<Vec<_>>(),
+            ["roles"]
+        );
+    }
+}
@@ -0,0 +1,15 @@
+//! Data types for VCO's API v2: resources such as `enterprises/{logicalId}/edges`, fetched with
+//! plain HTTP verbs and returned a page at a time.
+//!
+//! Where API v2 has the same things as API v1, such as entity IDs and logical IDs, `api_v1`'s
+//! types are used for them. States are kept as VCO's names for them, e.g. "IN_SERVICE".
+
+pub mod edge;
+pub mod enterprise;
+pub mod gateway;
+pub mod page;
+
+/// This is the first part of the URL path after the host to get to the API v2 resources.
+pub const API_BASE: &str = "api/sdwan/v2";
+
+pub type Map<K, V> = std::collections::BTreeMap<K, V>;
@@ -0,0 +1,91 @@
+//! Cursor pagination of API v2 collections.
+//!
+//! A collection comes back a page at a time, with `_metadata` saying whether there's `more`. The
+//! next page is fetched by passing its `nextPageLink` back as a query parameter.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use api_v1::unhandled::{impl_unhandled_fields, join_path, UnhandledFields};
+
+use crate::Map;
+
+/// The query parameter giving the most items to return in a page.
+pub const LIMIT_PARAM: &str = "limit";
+
+/// The query parameter giving the cursor of the page wanted.
+pub const NEXT_PAGE_PARAM: &str = "nextPageLink";
+
+/// One page of a collection of `T`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    #[serde(rename = "_metadata")]
+    pub metadata: PageMetadata,
+    pub data: Vec<T>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PageMetadata {
+    pub limit: Option<u32>,
+    pub more: bool,
+    /// The cursor of the next page, if there is one.
+    pub next_page_link: Option<String>,
+    #[serde(flatten)]
+    pub unhandled_fields: Map<String, Value>,
+}
+
+impl<T> Page<T> {
+    /// The cursor of the next page, or `None` if this is the last.
+    pub fn next_page(&self) -> Option<&str> {
+        match &self.metadata {
+            PageMetadata {
+                more: true,
+                next_page_link: Some(link),
+                ..
+            } => Some(link),
+            _ => None,
+        }
+    }
+}
+
+impl<T: UnhandledFields> UnhandledFields for Page<T> {
+    fn visit_unhandled(&self, path: &str, visit: &mut dyn FnMut(&str, &Value)) {
+        for (name, value) in &self.unhandled_fields {
+            visit(&join_path(path, name), value);
+        }
+        self.metadata
+            .visit_unhandled(&join_path(path, "_metadata"), visit);
+        self.data.visit_unhandled(&join_path(path, "data"), visit);
+    }
+}
+
+impl_unhandled_fields!(PageMetadata);
+
+#[cfg(test)]
+mod test_page {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_next_page() {
+        let page: Page<Value> = serde_json::from_value(json!({
+            "_metadata": {"limit": 2, "more": true, "nextPageLink": "b2Zmc2V0PTI="},
+            "data": [{"a": 1}, {"a": 2}]
+        }))
+        .unwrap();
+        assert_eq!(page.data.len(), 2);
+        assert_eq!(page.next_page(), Some("b2Zmc2V0PTI="));
+
+        let page: Page<Value> = serde_json::from_value(json!({
+            "_metadata": {"limit": 2, "more": false, "nextPageLink": null, "total": 3},
+            "data": [{"a": 3}]
+        }))
+        .unwrap();
+        assert_eq!(page.next_page(), None);
+        assert_eq!(page.metadata.unhandled_fields["total"], 3);
+    }
+}
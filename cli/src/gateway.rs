@@ -0,0 +1,151 @@
+use std::process::ExitCode;
+
+use anyhow::{anyhow, Result};
+use time::format_description::well_known::Rfc3339;
+use time::{Duration, OffsetDateTime};
+
+use client::client::Client as VcoClient;
+use client::model::{Certificate, Gateway, GatewayMetricSeries};
+
+/// Parse a period such as "30d": a whole number followed by one of "s", "m", "h", "d" or "w", for
+/// seconds, minutes, hours, days or weeks.
+pub(crate) fn parse_period(value: &str) -> Result<Duration, String> {
+    let bad_period = || format!("Not a period like \"30d\" or \"12h\": {value}");
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(bad_period)?;
+    let (count, unit) = value.split_at(split);
+    let count: i64 = count.parse().map_err(|_| bad_period())?;
+    let secs_per_unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(bad_period()),
+    };
+    count
+        .checked_mul(secs_per_unit)
+        .map(Duration::seconds)
+        .ok_or_else(|| format!("Too long a period: {value}"))
+}
+
+/// List the certificates of all the gateways, one per line, soonest to expire first.
+///
+/// If `expiring_within` is given, only the certificates that will have expired by then, or whose
+/// expiry isn't known, are listed, and the exit code is `FAILURE` if there are any, so that it can
+/// be used for alerting.
+pub(crate) async fn certs(
+    vco: &VcoClient,
+    expiring_within: Option<Duration>,
+) -> Result<(String, ExitCode)> {
+    let gateways = vco.get_gateway_certificates().await?;
+    let now = OffsetDateTime::now_utc();
+    let deadline = expiring_within
+        .map(|period| {
+            now.checked_add(period)
+                .ok_or_else(|| anyhow!("Can't tell what expires that far ahead."))
+        })
+        .transpose()?;
+
+    let mut certificates = gateways
+        .iter()
+        .flat_map(|gateway| {
+            let certificates = gateway.certificates.iter().flatten();
+            certificates.map(move |certificate| (gateway, certificate))
+        })
+        .filter(|(_, certificate)| {
+            deadline
+                .is_none_or(|when| certificate.expires().is_none() || certificate.expires_by(when))
+        })
+        .collect::<Vec<_>>();
+    // Those whose expiry isn't known go last.
+    certificates
+        .sort_by_key(|(_, certificate)| (certificate.expires().is_none(), certificate.expires()));
+
+    let exit_code = match deadline {
+        Some(_) if !certificates.is_empty() => ExitCode::FAILURE,
+        _ => ExitCode::SUCCESS,
+    };
+    let output = match deadline {
+        Some(when) if certificates.is_empty() => {
+            format!(
+                "No gateway certificates expire by {}.",
+                when.format(&Rfc3339)?
+            )
+        }
+        _ => certificates
+            .into_iter()
+            .map(|(gateway, certificate)| describe(gateway, certificate, now))
+            .collect::<Result<Vec<String>>>()?
+            .join("\n"),
+    };
+    Ok((output, exit_code))
+}
+
+/// A line about `gateway`'s `certificate`, e.g.
+/// "vcg01 0f:3a:91:2c expires 2033-01-01T00:00:00Z O=Example, CN=vcg01.example.com [DNS:...]".
+fn describe(gateway: &Gateway, certificate: &Certificate, now: OffsetDateTime) -> Result<String> {
+    let expiry = match certificate.expires() {
+        Some(expires) if expires <= now => format!("EXPIRED {}", expires.format(&Rfc3339)?),
+        Some(expires) => format!("expires {}", expires.format(&Rfc3339)?),
+        None => "expiry unknown".into(),
+    };
+    let details = match &certificate.details {
+        Some(details) => {
+            let names = details
+                .subject_alt_names
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{} [{names}]", details.subject)
+        }
+        None => "(certificate couldn't be parsed)".into(),
+    };
+    Ok(format!(
+        "{} {} {expiry} {details}",
+        gateway.name, certificate.serial_number
+    ))
+}
+
+/// A line per metric in `series` with its summary, e.g.
+/// "cpuPct: min 4.5, max 12.25, average 7.5 over 4 points".
+pub(crate) fn summarize_metrics(series: &GatewayMetricSeries) -> String {
+    let show = |value: Option<f64>| value.map_or("-".into(), |value| value.to_string());
+    series
+        .metrics
+        .iter()
+        .map(|(metric, values)| {
+            format!(
+                "{metric}: min {}, max {}, average {} over {} points",
+                show(values.summary.min),
+                show(values.summary.max),
+                show(values.summary.average),
+                values.points.len()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test_gateway {
+    use super::*;
+
+    #[test]
+    fn test_parse_period() {
+        assert_eq!(parse_period("30d"), Ok(Duration::days(30)));
+        assert_eq!(parse_period("12h"), Ok(Duration::hours(12)));
+        assert_eq!(parse_period("2w"), Ok(Duration::weeks(2)));
+        assert_eq!(parse_period("90m"), Ok(Duration::minutes(90)));
+        assert_eq!(
+            parse_period("9223372036854775807s"),
+            Ok(Duration::seconds(i64::MAX))
+        );
+        assert!(parse_period("9223372036854775807w").is_err());
+        for bad in ["", "30", "d", "30x", "-3d", "3.5d", "30 d"] {
+            assert!(parse_period(bad).is_err(), "{bad}");
+        }
+    }
+}
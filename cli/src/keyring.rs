@@ -25,9 +25,9 @@ fn get_secret_for_user(credential_type: &str, username: &str) -> Result<String>
 
 /// Create or update a keyring entry.
 fn set_credential(vco_fqdn: &str, username: &str, credential_type: &str) -> Result<String> {
-    let cred_name = get_cred_name(&credential_type, &username);
-    let secret = get_secret_for_user(&credential_type, &username)?;
-    let entry = KeyringEntry::new(&vco_fqdn, &cred_name)?;
+    let cred_name = get_cred_name(credential_type, username);
+    let secret = get_secret_for_user(credential_type, username)?;
+    let entry = KeyringEntry::new(vco_fqdn, &cred_name)?;
     entry.set_password(&secret)?;
     Ok(format!(
         "Set {credential_type} for {username} on {vco_fqdn}."
@@ -36,15 +36,15 @@ fn set_credential(vco_fqdn: &str, username: &str, credential_type: &str) -> Resu
 
 /// Read the credential from the keyring.
 fn get_credential(vco_fqdn: &str, username: &str, credential_type: &str) -> Result<String> {
-    let cred_name = get_cred_name(&credential_type, &username);
-    let entry = KeyringEntry::new(&vco_fqdn, &cred_name)?;
+    let cred_name = get_cred_name(credential_type, username);
+    let entry = KeyringEntry::new(vco_fqdn, &cred_name)?;
     Ok(entry.get_password()?)
 }
 
 /// Delete the credential from the keyring.
 fn delete_credential(vco_fqdn: &str, username: &str, credential_type: &str) -> Result<String> {
-    let cred_name = get_cred_name(&credential_type, &username);
-    let entry = KeyringEntry::new(&vco_fqdn, &cred_name)?;
+    let cred_name = get_cred_name(credential_type, username);
+    let entry = KeyringEntry::new(vco_fqdn, &cred_name)?;
     entry.delete_password()?;
     Ok(format!(
         "Deleted {username}'s {credential_type} for {vco_fqdn} from keyring"
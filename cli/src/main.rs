@@ -1,33 +1,101 @@
+use std::process::ExitCode;
+
 use anyhow::Result;
 use clap::{Args, Parser, Subcommand};
 
-// TODO this api_v1 stuff should be in `client` at best and not here.
-use api_v1::date_time::DateTime;
-use api_v1::gateway::GatewayMetric;
-use client::client::Client as VcoClient;
-
+use client::builder::VcoClientBuilder;
+use client::client::{Client as VcoClient, DeserializeProfile};
+use client::credentials::{CredentialProvider, Credentials};
+use client::id::GatewayId;
+use client::model::Metric;
+use client::session::SessionCache;
+use client::Url;
+use time::format_description::well_known::Rfc3339;
+use time::{Duration, OffsetDateTime};
+
+mod gateway;
 mod keyring;
 mod property;
 
-/// Build a `VcoClient` given the VCO's FQDN and credentials.
-async fn client_from_creds(vco_fqdn: &str, creds_source: &CredentialSource) -> Result<VcoClient> {
+/// Start building a `VcoClient` for the VCO, given either its FQDN or its full base URL, that
+/// reads responses with `profile`.
+fn vco_builder(vco_fqdn: &str, profile: DeserializeProfile) -> Result<VcoClientBuilder> {
+    let builder = if vco_fqdn.contains("://") {
+        VcoClientBuilder::from_url(Url::parse(vco_fqdn)?)
+    } else {
+        VcoClientBuilder::new(vco_fqdn)
+    };
+    Ok(builder.deserialize_profile(profile))
+}
+
+/// Where to save password sessions for reuse by later runs: the user's cache directory, unless
+/// `use_session_cache` is `false`. Token sessions aren't saved.
+fn session_cache(creds_source: &CredentialSource, use_session_cache: bool) -> Option<SessionCache> {
+    (use_session_cache && creds_source.is_password())
+        .then(SessionCache::in_user_cache_dir)
+        .flatten()
+}
+
+/// Save `vco`'s session in `cache`, if there is one, for the next run to resume.
+fn save_session(vco: &VcoClient, cache: Option<&SessionCache>) {
+    if let (Some(cache), Some(session)) = (cache, vco.session()) {
+        if let Err(e) = cache.store(&session) {
+            eprintln!("Warning: couldn't save the session for next time: {e}");
+        }
+    }
+}
+
+/// Build a `VcoClient` given the VCO's FQDN and credentials, that reads responses with `profile`.
+///
+/// A password session saved in `cache` is resumed if it's still good, and a new one is saved
+/// there.
+async fn client_from_creds(
+    vco_fqdn: &str,
+    creds_source: &CredentialSource,
+    cache: Option<&SessionCache>,
+    profile: DeserializeProfile,
+) -> Result<VcoClient> {
     let vco = if creds_source.is_token() {
-        let (_, token) = creds_source.acquire(&vco_fqdn)?;
-        VcoClient::operator_login_token(&vco_fqdn, &token)
+        let (_, token) = creds_source.acquire(vco_fqdn)?;
+        vco_builder(vco_fqdn, profile)?
+            .login_token(&token)
             .await
             .map_err(|_| {
                 anyhow::format_err!("Could not log into {vco_fqdn} with the given token.")
             })?
     }
     else if creds_source.is_password() {
-        let (username, password) = creds_source.acquire(&vco_fqdn)?;
-        VcoClient::operator_login_password(&vco_fqdn, &username, &password)
+        let username = creds_source.username().expect("password sources have a username");
+        let builder = vco_builder(vco_fqdn, profile)?
+            .credential_provider(creds_source.provider(vco_fqdn));
+        let base_url = builder.base_url()?;
+
+        // Pick up where the last run left off if we can, to save logging in again.
+        if let Some(cache) = cache {
+            if let Some(session) = cache.load(&base_url, username) {
+                match builder.clone().resume_session(&session).await {
+                    Ok(vco) => return Ok(vco),
+                    // It's no good, so don't try it again next time.
+                    Err(_) => {
+                        if let Err(e) = cache.remove(&base_url, username) {
+                            eprintln!("Warning: couldn't remove the stale session: {e}");
+                        }
+                    }
+                }
+            }
+        }
+
+        let (username, password) = creds_source.acquire(vco_fqdn)?;
+        let vco = builder
+            .login_password(&username, &password)
             .await
             .map_err(|e| {
                 anyhow::format_err!(
                     "Could not log into {vco_fqdn} as {username} with the given password...\n{e:?}."
                 )
-            })?
+            })?;
+        save_session(&vco, cache);
+        vco
     }
     else {
         unreachable!()
@@ -52,7 +120,7 @@ fn is_email(value: &str) -> Result<String, String> {
 ///
 /// This struct defines multiple mutually-exclusive options for sources of credentials and is
 /// included in several places in the Command-Line Interface definitions below.
-#[derive(Args, Debug)]
+#[derive(Args, Clone, Debug)]
 #[group(required = true, multiple = false)]
 struct CredentialSource {
     /// Prompt for the password of this user on the command-line.
@@ -79,26 +147,26 @@ impl CredentialSource {
             // Prompt on the command line for the user's password.
             Ok((
                 username.to_string(),
-                rpassword::prompt_password(&format!("Password for {username} on {vco_fqdn}: "))?,
+                rpassword::prompt_password(format!("Password for {username} on {vco_fqdn}: "))?,
             ))
         } else if self.token {
             // Prompt on the command line for a token on the VCO.
             // We don't need the user name here; the VCO knows who owns it.
             Ok((
                 String::new(),
-                rpassword::prompt_password(&format!("API token for {vco_fqdn}: "))?,
+                rpassword::prompt_password(format!("API token for {vco_fqdn}: "))?,
             ))
         } else if let Some(username) = &self.keyring_token {
             // Get the user's token from the system keyring, if it exists.
             Ok((
                 username.to_string(),
-                keyring::get_token(&vco_fqdn, &username)?,
+                keyring::get_token(vco_fqdn, username)?,
             ))
         } else if let Some(username) = &self.keyring_password {
             // Get the user's password from the system token, if it exists.
             Ok((
                 username.to_string(),
-                keyring::get_password(&vco_fqdn, &username)?,
+                keyring::get_password(vco_fqdn, username)?,
             ))
         } else {
             // There may be other sources in future...
@@ -106,6 +174,25 @@ impl CredentialSource {
         }
     }
 
+    /// The username given, if any.
+    fn username(&self) -> Option<&str> {
+        self.prompt
+            .as_deref()
+            .or(self.keyring_token.as_deref())
+            .or(self.keyring_password.as_deref())
+    }
+
+    /// A `CredentialProvider` that acquires the credential again, for logging in again when the
+    /// session expires.
+    fn provider(&self, vco_fqdn: &str) -> CredentialProvider {
+        let source = self.clone();
+        let vco_fqdn = vco_fqdn.to_string();
+        CredentialProvider::new(move || {
+            let (username, password) = source.acquire(&vco_fqdn).ok()?;
+            Some(Credentials::new(&username, &password))
+        })
+    }
+
     // Is the credential a token?
     fn is_token(&self) -> bool {
         self.token || self.keyring_token.is_some()
@@ -126,8 +213,19 @@ impl CredentialSource {
 #[command(name = "vcoctl")]
 #[command(about = "CLI tool for interacting with VMware SD-WAN Orchestrator")]
 struct Cli {
+    /// The VCO's FQDN, optionally with a port, e.g. "vco01.example.com:8443"; or its full base
+    /// URL, e.g. "http://127.0.0.1:8080/".
     vco_fqdn: String,
 
+    /// Don't reuse a saved login session, or save this one for next time.
+    #[arg(long, global = true)]
+    no_session_cache: bool,
+
+    /// Accept the encodings some VCO releases use in place of the documented ones, and values
+    /// this tool doesn't know, rather than failing.
+    #[arg(long, global = true)]
+    lenient: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -154,6 +252,15 @@ enum Commands {
         action: PropertyCommand,
     },
 
+    /// Actions on VCGs.
+    Gateway {
+        #[command(flatten)]
+        creds_source: CredentialSource,
+
+        #[command(subcommand)]
+        action: GatewayCommand,
+    },
+
     /// Actions on VCG metrics.
     GatewayMetric {
         #[command(flatten)]
@@ -177,6 +284,18 @@ enum KeyringCommand {
     DeleteToken,
 }
 
+/// Gateway commands.
+#[derive(Debug, Subcommand)]
+enum GatewayCommand {
+    /// List the gateways' certificates, soonest to expire first.
+    Certs {
+        /// Only list certificates that expire within this period, e.g. "30d", or whose expiry
+        /// isn't known, and exit with a non-zero status if there are any.
+        #[arg(long, value_name = "PERIOD", value_parser(gateway::parse_period))]
+        expiring_within: Option<Duration>,
+    },
+}
+
 /// VCO System Property commands
 #[derive(Debug, Subcommand)]
 enum PropertyCommand {
@@ -208,9 +327,15 @@ enum PropertyCommand {
 /// This is the entry point to this CLI program.
 /// TODO return an appropriate value to the terminal emulator on error, or `0` in success.
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let args = Cli::parse();
     let vco_fqdn = args.vco_fqdn;
+    let use_session_cache = !args.no_session_cache;
+    let profile = if args.lenient {
+        DeserializeProfile::Lenient
+    } else {
+        DeserializeProfile::Strict
+    };
 
     // Take action depending on the parameters passed in, and wait for some sort of output to print.
     // The actual action code is held in separate modules in this `cli` crate.
@@ -228,8 +353,9 @@ async fn main() -> Result<()> {
             creds_source,
             action,
         } => {
-            let vco = client_from_creds(&vco_fqdn, &creds_source).await?;
-            match action {
+            let cache = session_cache(&creds_source, use_session_cache);
+            let vco = client_from_creds(&vco_fqdn, &creds_source, cache.as_ref(), profile).await?;
+            let output = match action {
                 PropertyCommand::List {
                     filter,
                     show_passwords,
@@ -238,25 +364,46 @@ async fn main() -> Result<()> {
                 // PropertyCommand::Get { name } => {}
                 // PropertyCommand::Set => {}
                 // PropertyCommand::Delete => {}
+            };
+            // Logging in again along the way gives a new session.
+            save_session(&vco, cache.as_ref());
+            output
+        }
+
+        Commands::Gateway {
+            creds_source,
+            action,
+        } => {
+            let cache = session_cache(&creds_source, use_session_cache);
+            let vco = client_from_creds(&vco_fqdn, &creds_source, cache.as_ref(), profile).await?;
+            match action {
+                GatewayCommand::Certs { expiring_within } => {
+                    let (output, exit_code) = gateway::certs(&vco, expiring_within).await?;
+                    save_session(&vco, cache.as_ref());
+                    println!("{output}");
+                    return Ok(exit_code);
+                }
             }
         }
 
         Commands::GatewayMetric { creds_source } => {
-            let vco = client_from_creds(&vco_fqdn, &creds_source).await?;
+            let cache = session_cache(&creds_source, use_session_cache);
+            let vco = client_from_creds(&vco_fqdn, &creds_source, cache.as_ref(), profile).await?;
 
-            let start = DateTime::from_rfc3339("2023-06-18T12:00:00Z").unwrap();
+            let start = OffsetDateTime::parse("2023-06-18T12:00:00Z", &Rfc3339).unwrap();
             let result = vco
                 .get_gateway_status_metrics(
-                    80,
-                    &start,
+                    GatewayId(80),
+                    start,
                     None,
-                    &[GatewayMetric::MemoryPct, GatewayMetric::CpuPct], //, GatewayMetric::ConnectedEdges],
+                    &[Metric::MemoryPct, Metric::CpuPct], //, Metric::ConnectedEdges],
                 )
                 .await;
-            result?
+            save_session(&vco, cache.as_ref());
+            gateway::summarize_metrics(&result?)
         }
     };
     println!("{}", output_message);
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
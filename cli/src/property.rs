@@ -6,7 +6,7 @@ use client::client::Client as VcoClient;
 ///
 /// TODO consideration should be given to the other parameters of each property.
 /// TODO currently the response is a newline-separated string. It should return
-///      `Vec<(String, PropertyParams)>`.
+///      `Vec<SystemProperty>`.
 ///
 /// If `filter` is specified, then only properties whose names start with the filter string will be
 /// shown. If `show_passwords` is `false` then any properties marked as `isPassword` in the response
@@ -15,17 +15,13 @@ pub(crate) async fn list(vco: &VcoClient, filter: &str, show_passwords: bool) ->
     let result = vco.get_system_properties().await?;
     let result = result
         .into_iter()
-        .filter(|item| item.property.name.starts_with(filter))
-        .map(|item| {
-            format!(
-                "{} => {}",
-                item.property.name,
-                if show_passwords || !item.property.is_password.0 {
-                    &item.property.value
-                } else {
-                    "****"
-                }
-            )
+        .filter(|property| property.name.starts_with(filter))
+        .map(|property| {
+            if show_passwords || !property.is_password {
+                format!("{} => {}", property.name, property.value)
+            } else {
+                format!("{} => ****", property.name)
+            }
         })
         .collect::<Vec<String>>()
         .join("\n");
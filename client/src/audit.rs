@@ -0,0 +1,107 @@
+//! Auditing VCO's responses for fields that `api_v1` doesn't model.
+//!
+//! The types in `api_v1` keep fields they don't know about rather than dropping them, but nothing
+//! looks at them. With a `FieldAudit` a `Client` checks every response for such fields, so that
+//! API drift after a VCO upgrade shows up in the logs or a report instead of going unnoticed.
+
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+use crate::Map;
+
+/// What a `Client` does about fields in VCO's responses that `api_v1` doesn't model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FieldAudit {
+    /// Don't look for them.
+    #[default]
+    Off,
+    /// Log a warning the first time each one is seen, as well as collecting them.
+    Log,
+    /// Quietly collect them, for `Client::unhandled_field_report`.
+    Collect,
+}
+
+/// The fields VCO returned that `api_v1` doesn't model, by the method that returned them.
+///
+/// Paths are as given by `api_v1::unhandled::UnhandledFields`, e.g.
+/// `[].handOffDetail.icmpProbe.newField`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnhandledFieldReport {
+    fields: Map<String, BTreeSet<String>>,
+}
+
+impl UnhandledFieldReport {
+    /// Was everything VCO returned modeled?
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// The methods that returned unhandled fields.
+    pub fn methods(&self) -> impl Iterator<Item = &str> {
+        self.fields.keys().map(String::as_str)
+    }
+
+    /// The paths of the unhandled fields returned by `method`.
+    pub fn paths(&self, method: &str) -> impl Iterator<Item = &str> {
+        self.fields
+            .get(method)
+            .into_iter()
+            .flat_map(|paths| paths.iter().map(String::as_str))
+    }
+
+    /// Note an unhandled field, returning whether it's new.
+    pub(crate) fn add(&mut self, method: &str, path: &str) -> bool {
+        self.fields
+            .entry(method.into())
+            .or_default()
+            .insert(path.into())
+    }
+}
+
+/// One line per field, e.g. `network/getNetworkGateways: [].newField`.
+impl Display for UnhandledFieldReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (method, paths) in &self.fields {
+            for path in paths {
+                writeln!(f, "{method}: {path}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_audit {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let mut report = UnhandledFieldReport::default();
+        assert!(report.is_empty());
+        assert!(report.add("network/getNetworkGateways", "[].b"));
+        assert!(report.add("network/getNetworkGateways", "[].a"));
+        assert!(!report.add("network/getNetworkGateways", "[].b"));
+        assert!(report.add("enterpriseUser/getEnterpriseUser", "x"));
+
+        assert_eq!(
+            report.methods().collect::<Vec<_>>(),
+            vec![
+                "enterpriseUser/getEnterpriseUser",
+                "network/getNetworkGateways"
+            ]
+        );
+        assert_eq!(
+            report
+                .paths("network/getNetworkGateways")
+                .collect::<Vec<_>>(),
+            vec!["[].a", "[].b"]
+        );
+        assert_eq!(report.paths("logout").count(), 0);
+        assert_eq!(
+            report.to_string(),
+            "enterpriseUser/getEnterpriseUser: x\n\
+             network/getNetworkGateways: [].a\n\
+             network/getNetworkGateways: [].b\n"
+        );
+    }
+}
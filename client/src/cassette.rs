@@ -0,0 +1,365 @@
+//! Recording API calls to a file, and replaying them later without VCO.
+//!
+//! A `Cassette` in record mode writes each call a `Client` makes, and the raw response it got, to
+//! a JSON file. One in replay mode answers calls from that file instead of the network, so tests
+//! can run in CI against responses captured once from a real orchestrator.
+//!
+//! Secrets are scrubbed before anything is written: passwords, tokens, secrets and activation keys
+//! in parameters and responses, and the values of system properties marked `isPassword`. A
+//! response body that isn't JSON can't be scrubbed field by field, so it's replaced whole. Headers,
+//! and so API tokens and session cookies, are never recorded.
+
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::ClientError;
+use crate::transport::{Transport, Verb};
+
+/// What scrubbed values are replaced with.
+pub const SCRUBBED: &str = "****";
+
+/// Fragments of the (lower-cased) names of fields whose values are secret.
+const SECRET_FIELD_FRAGMENTS: &[&str] = &["password", "token", "secret", "activationkey"];
+
+/// Whether a `Cassette` records calls or replays them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Make calls to VCO as normal, writing each one to the cassette file.
+    Record,
+    /// Answer calls from the cassette file, without touching the network. A call that wasn't
+    /// recorded fails with `ClientError::CassetteUnmatched`.
+    Replay,
+}
+
+/// Records a `Client`'s calls to a file, or replays them from it. Give one to
+/// `VcoClientBuilder::cassette`.
+///
+/// Cloning a `Cassette` gives a handle to the same recording.
+#[derive(Clone)]
+pub struct Cassette {
+    mode: CassetteMode,
+    path: PathBuf,
+    interactions: Arc<Mutex<Vec<Interaction>>>,
+}
+
+/// One recorded call and its response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Interaction {
+    transport: Transport,
+    /// Only recorded for API v2 calls, since API v1 calls are all `POST`s.
+    #[serde(default, skip_serializing_if = "Verb::is_post")]
+    verb: Verb,
+    method: String,
+    params: Option<Value>,
+    status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_after: Option<u64>,
+    /// The response body, if it was JSON.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    json: Option<Value>,
+    /// `SCRUBBED`, if the response body wasn't JSON.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    /// Set once replayed, so that repeated calls get the responses in the order recorded.
+    #[serde(skip)]
+    played: bool,
+}
+
+/// The file a `Cassette` is stored in.
+#[derive(Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+/// A response from VCO, as it came off the wire.
+#[derive(Debug, Clone)]
+pub(crate) struct RawResponse {
+    pub(crate) status: StatusCode,
+    pub(crate) retry_after: Option<Duration>,
+    pub(crate) text: String,
+}
+
+impl Cassette {
+    /// Record calls to `path`, replacing anything already there. The file is rewritten after
+    /// every call, so it's complete however the program ends.
+    pub fn record(path: impl AsRef<Path>) -> Self {
+        Self {
+            mode: CassetteMode::Record,
+            path: path.as_ref().into(),
+            interactions: Arc::default(),
+        }
+    }
+
+    /// Replay the calls recorded in `path`.
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self, ClientError> {
+        let text = std::fs::read_to_string(&path).map_err(ClientError::CassetteIo)?;
+        let file: CassetteFile = serde_json::from_str(&text).map_err(ClientError::Json)?;
+        if let Some(bad) = file
+            .interactions
+            .iter()
+            .find(|interaction| StatusCode::from_u16(interaction.status).is_err())
+        {
+            return Err(ClientError::CassetteIo(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Bad HTTP status {} recorded for {}", bad.status, bad.method),
+            )));
+        }
+        Ok(Self {
+            mode: CassetteMode::Replay,
+            path: path.as_ref().into(),
+            interactions: Arc::new(Mutex::new(file.interactions)),
+        })
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// How many recorded calls haven't been replayed yet.
+    pub fn unplayed(&self) -> usize {
+        self.interactions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|interaction| !interaction.played)
+            .count()
+    }
+
+    /// Note a call and its response, and save the cassette.
+    pub(crate) fn record_call(
+        &self,
+        transport: Transport,
+        verb: Verb,
+        method: &str,
+        params: Option<Value>,
+        response: &RawResponse,
+    ) -> Result<(), ClientError> {
+        let (json, text) = match serde_json::from_str::<Value>(&response.text) {
+            Ok(mut json) => {
+                scrub(&mut json);
+                (Some(json), None)
+            }
+            Err(_) if response.text.is_empty() => (None, None),
+            Err(_) => (None, Some(SCRUBBED.into())),
+        };
+        let interaction = Interaction {
+            transport,
+            verb,
+            method: method.into(),
+            params: params.map(scrubbed),
+            status: response.status.as_u16(),
+            retry_after: response.retry_after.map(|delay| delay.as_secs()),
+            json,
+            text,
+            played: false,
+        };
+
+        let mut interactions = self.interactions.lock().unwrap();
+        interactions.push(interaction);
+        let file = CassetteFile {
+            interactions: interactions.clone(),
+        };
+        let text = serde_json::to_string_pretty(&file).map_err(ClientError::Json)?;
+        std::fs::write(&self.path, text).map_err(ClientError::CassetteIo)
+    }
+
+    /// Find the first unplayed recording of the call, and give its response.
+    pub(crate) fn play_call(
+        &self,
+        transport: Transport,
+        verb: Verb,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<RawResponse, ClientError> {
+        let params = params.map(scrubbed);
+        let mut interactions = self.interactions.lock().unwrap();
+        let interaction = interactions
+            .iter_mut()
+            .find(|interaction| {
+                !interaction.played
+                    && interaction.transport == transport
+                    && interaction.verb == verb
+                    && interaction.method == method
+                    && interaction.params == params
+            })
+            .ok_or_else(|| ClientError::CassetteUnmatched {
+                method: method.into(),
+                params: params
+                    .as_ref()
+                    .map(Value::to_string)
+                    .unwrap_or_else(|| "none".into()),
+            })?;
+        interaction.played = true;
+
+        Ok(RawResponse {
+            status: StatusCode::from_u16(interaction.status)
+                .expect("Statuses are checked when the cassette is loaded"),
+            retry_after: interaction.retry_after.map(Duration::from_secs),
+            text: match (&interaction.json, &interaction.text) {
+                (Some(json), _) => json.to_string(),
+                (None, Some(text)) => text.clone(),
+                (None, None) => String::new(),
+            },
+        })
+    }
+}
+
+impl Debug for Cassette {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cassette({:?}, {:?})", self.mode, self.path)
+    }
+}
+
+fn scrubbed(mut value: Value) -> Value {
+    scrub(&mut value);
+    value
+}
+
+/// Replace secrets in `value` with `SCRUBBED`, wherever they are.
+fn scrub(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            // System properties flag their own secrets.
+            let is_password = object
+                .get("isPassword")
+                .is_some_and(|flag| flag == &Value::from(1) || flag == &Value::Bool(true));
+            for (name, value) in object.iter_mut() {
+                let secret = (is_password && (name == "value" || name == "defaultValue"))
+                    || is_secret_field(name);
+                match value {
+                    Value::String(_) | Value::Number(_) | Value::Bool(_) if secret => {
+                        *value = SCRUBBED.into()
+                    }
+                    _ => scrub(value),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(scrub),
+        _ => {}
+    }
+}
+
+fn is_secret_field(name: &str) -> bool {
+    // Whether something is a secret isn't itself secret, e.g. `isPassword`.
+    if name
+        .strip_prefix("is")
+        .is_some_and(|rest| rest.starts_with(char::is_uppercase))
+    {
+        return false;
+    }
+    let name = name.to_ascii_lowercase();
+    // When a secret expires isn't itself secret, e.g. `activationKeyExpires`.
+    !name.ends_with("expires")
+        && SECRET_FIELD_FRAGMENTS
+            .iter()
+            .any(|fragment| name.contains(fragment))
+}
+
+#[cfg(test)]
+mod test_cassette {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_scrub() {
+        let value = scrubbed(json!({
+            "username": "alice@example.com",
+            "password": "hunter2",
+            "pin": {"secretCode": 1234},
+            "data": {"apiToken": "abc", "isPassword": 0},
+            "gateways": [{
+                "activationKey": "ABCD-EFGH-IJKL-MNOP",
+                "activationKeyExpires": "2024-01-01T00:00:00.000Z",
+                "subjectKeyId": "AB:CD",
+            }],
+            "properties": [
+                {"name": "mail.smtp.auth.pass", "value": "s3cret", "isPassword": 1},
+                {"name": "product.version", "value": "4.5.1", "isPassword": 0},
+                {"name": "other.secret", "value": null, "defaultValue": "x", "isPassword": true},
+                {"name": "vpn.psk", "value": 5678, "isPassword": 1},
+            ]
+        }));
+        assert_eq!(
+            value,
+            json!({
+                "username": "alice@example.com",
+                "password": "****",
+                "pin": {"secretCode": "****"},
+                "data": {"apiToken": "****", "isPassword": 0},
+                "gateways": [{
+                    "activationKey": "****",
+                    "activationKeyExpires": "2024-01-01T00:00:00.000Z",
+                    "subjectKeyId": "AB:CD",
+                }],
+                "properties": [
+                    {"name": "mail.smtp.auth.pass", "value": "****", "isPassword": 1},
+                    {"name": "product.version", "value": "4.5.1", "isPassword": 0},
+                    {"name": "other.secret", "value": null, "defaultValue": "****", "isPassword": true},
+                    {"name": "vpn.psk", "value": "****", "isPassword": 1},
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_replay_in_order() {
+        let path =
+            std::env::temp_dir().join(format!("vco-rs-test-cassette-{}.json", std::process::id()));
+        let recorder = Cassette::record(&path);
+        for (status, text) in [
+            (503, ""),
+            (502, "<html>password=hunter2</html>"),
+            (200, "[1]"),
+        ] {
+            let response = RawResponse {
+                status: StatusCode::from_u16(status).unwrap(),
+                retry_after: None,
+                text: text.into(),
+            };
+            recorder
+                .record_call(
+                    Transport::Rest,
+                    Verb::Post,
+                    "network/getNetworkGateways",
+                    None,
+                    &response,
+                )
+                .unwrap();
+        }
+
+        let player = Cassette::replay(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(player.unplayed(), 3);
+
+        // Nothing is replayed for a different transport.
+        assert!(player
+            .play_call(
+                Transport::JsonRpc,
+                Verb::Post,
+                "network/getNetworkGateways",
+                None
+            )
+            .is_err());
+
+        let call = || {
+            player.play_call(
+                Transport::Rest,
+                Verb::Post,
+                "network/getNetworkGateways",
+                None,
+            )
+        };
+        assert_eq!(call().unwrap().status, StatusCode::SERVICE_UNAVAILABLE);
+        // A body that isn't JSON is never written as it was.
+        assert_eq!(call().unwrap().text, SCRUBBED);
+        assert_eq!(call().unwrap().text, "[1]");
+        assert!(matches!(call(), Err(ClientError::CassetteUnmatched { .. })));
+    }
+}
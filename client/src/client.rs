@@ -3,26 +3,80 @@
 
 // TODO we need a way for users to be able to specify relative or absolute datetimes.
 
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
 use reqwest::header::HeaderMap;
-use reqwest::ClientBuilder;
+use reqwest::{StatusCode, Url};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 
+use crate::audit::{FieldAudit, UnhandledFieldReport};
+use crate::builder::VcoClientBuilder;
+use crate::cassette::{Cassette, CassetteMode, RawResponse};
+use crate::credentials::CredentialProvider;
 use crate::error::{ClientError, Error as ApiError};
+use crate::identity::{Identity, Principal};
+use crate::retry::{parse_retry_after, RetryPolicy};
+use crate::session::{Session, SessionCookies};
+use crate::throttle::Throttle;
+use crate::transport::{Transport, Verb};
+use crate::version::{VcoVersion, VERSION_PROPERTY};
+use api_v1::common::RowsModifiedConfirmation;
 pub use api_v1::error::SerdeError;
-pub use api_v1::gateway::{GatewayMetric, NetworkGetNetworkGatewaysResultItem};
 use api_v1::login::AuthObject;
-pub use api_v1::property::GetSystemPropertiesResultItem;
+use api_v1::profile::with_profile;
+pub use api_v1::profile::DeserializeProfile;
+use api_v1::property::GetSystemPropertiesResultItem;
+use api_v1::unhandled::UnhandledFields;
+use api_v1::user::User;
+use api_v2::page::{Page, LIMIT_PARAM, NEXT_PAGE_PARAM};
+
+/// The most items to ask for in each page of an API v2 collection.
+const PAGE_LIMIT: u32 = 500;
 
 /// Use `Client` to make calls to VCO's API. This is the main interface to the rest of the `client`
 /// crate.
 ///
 /// The intention with `Client` is to insulate callers from the vagaries of the different underlying
 /// VCO APIs, where they have changed over time, presenting a predictable API.
+#[derive(Debug)]
 pub struct Client {
     pub(crate) client: reqwest::Client,
-    pub(crate) hostname: String,
-    pub(crate) domain: String,
+    /// Where VCO is, e.g. `https://vco01.example.com/`. Always ends in a `/`.
+    pub(crate) base_url: Url,
+    pub(crate) transport: Transport,
+    pub(crate) retry_policy: RetryPolicy,
+    pub(crate) throttle: Throttle,
+    /// Who we're logged in as, once we know.
+    pub(crate) identity: Option<Identity>,
+    /// The release of VCO we're talking to, if we know.
+    pub(crate) version: Option<VcoVersion>,
+    /// Whether VCO has API v2's resources, once we know.
+    pub(crate) api_v2: Option<bool>,
+    /// Source of JSON-RPC request IDs.
+    pub(crate) next_request_id: AtomicU64,
+    /// Where to get credentials for logging in again when the session expires.
+    pub(crate) credential_provider: Option<CredentialProvider>,
+    /// Held while logging in again, so that calls finding the session expired at the same time
+    /// only log in once.
+    pub(crate) relogin_lock: Mutex<()>,
+    /// Counts logins, so that a call can tell whether someone else has already logged in again
+    /// since it started.
+    pub(crate) session_generation: AtomicU64,
+    /// The cookies `client` sends, including VCO's session cookie.
+    pub(crate) cookies: Arc<SessionCookies>,
+    /// Where calls are recorded to or replayed from, if anywhere.
+    pub(crate) cassette: Option<Cassette>,
+    /// What to do about fields in responses that `api_v1` doesn't model.
+    pub(crate) field_audit: FieldAudit,
+    /// The unhandled fields found so far.
+    pub(crate) unhandled_fields: std::sync::Mutex<UnhandledFieldReport>,
+    /// How forgiving to be of encodings that have drifted between VCO releases.
+    pub(crate) deserialize_profile: DeserializeProfile,
 }
 
 impl Client {
@@ -30,116 +84,628 @@ impl Client {
     // LOGIN METHODS
     //
 
-    /// Do an authenticate with username and password.
+    /// Do an authenticate with username and password, with the default client settings.
     ///
-    /// This authentication method uses cookies from the get-go.
+    /// Use `VcoClientBuilder` to change those settings.
     pub async fn operator_login_password(
         fqdn: &str,
         username: &str,
         password: &str,
     ) -> Result<Self, ClientError> {
-        let (hostname, domain) = fqdn_to_name_and_domain(fqdn)?;
-        let default_headers = Self::common_client_headers(&hostname, &domain);
-        let client_builder = Self::common_client_builder(default_headers);
-        let req_client = client_builder
-            .build()
-            .map_err(ClientError::ReqwestClientCreate)?;
-
-        let client = Self {
-            client: req_client,
-            hostname,
-            domain,
-        };
+        VcoClientBuilder::new(fqdn)
+            .login_password(username, password)
+            .await
+    }
 
-        // Build the request body.
-        let auth_object = AuthObject::new(username.into(), password.into());
+    /// Do token-based auth, with the default client settings.
+    ///
+    /// Use `VcoClientBuilder` to change those settings.
+    pub async fn operator_login_token(fqdn: &str, token: &str) -> Result<Self, ClientError> {
+        VcoClientBuilder::new(fqdn).login_token(token).await
+    }
 
-        // Do the actual login. The response body is empty so we just discard it.
-        client
-            .post_with_payload("login/operatorLogin", &auth_object)
-            .await?;
+    /// Log in as an enterprise or partner (MSP) admin with username and password, with the
+    /// default client settings.
+    ///
+    /// Use `VcoClientBuilder` to change those settings.
+    pub async fn enterprise_login_password(
+        fqdn: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<Self, ClientError> {
+        VcoClientBuilder::new(fqdn)
+            .login_enterprise_password(username, password)
+            .await
+    }
 
-        Ok(client)
+    /// Do token-based auth as an enterprise or partner (MSP) admin, with the default client
+    /// settings.
+    ///
+    /// Use `VcoClientBuilder` to change those settings.
+    pub async fn enterprise_login_token(fqdn: &str, token: &str) -> Result<Self, ClientError> {
+        VcoClientBuilder::new(fqdn)
+            .login_enterprise_token(token)
+            .await
     }
 
-    /// Do token-based auth.
-    pub async fn operator_login_token(fqdn: &str, token: &str) -> Result<Self, ClientError> {
-        let (hostname, domain) = fqdn_to_name_and_domain(fqdn)?;
-        // Set up default headers.
-        let mut default_headers = Self::common_client_headers(&hostname, &domain);
-        default_headers.insert(
-            reqwest::header::AUTHORIZATION,
-            format!("Token {token}").parse().unwrap(),
-        );
+    /// Ask VCO who we are, confirming that our session or token is good. `operator` says whether
+    /// we logged in as an operator, which decides how we have to ask.
+    pub(crate) async fn identify(&mut self, operator: bool) -> Result<(), ClientError> {
+        let identity = if operator {
+            let user = self.whoami("operatorUser/getOperatorUser").await?;
+            Identity::new(user, Principal::Operator)
+        } else {
+            match self.whoami("enterpriseUser/getEnterpriseUser").await {
+                Ok(user) => {
+                    let principal = Identity::enterprise_login_principal(&user);
+                    Identity::new(user, principal)
+                }
+                // Partner admins aren't enterprise users, so VCO won't look them up as one.
+                Err(ClientError::Api(_)) => {
+                    let user = self
+                        .whoami("enterpriseProxy/getEnterpriseProxyUser")
+                        .await?;
+                    Identity::new(user, Principal::Partner)
+                }
+                Err(e) => return Err(e),
+            }
+        };
+        self.identity = Some(identity);
+        self.detect_version().await;
+        self.detect_api_v2().await;
+        Ok(())
+    }
 
-        // Set up client builder.
-        let client_builder = Self::common_client_builder(default_headers);
+    /// Find out which release of VCO we're talking to, unless we've been told already.
+    ///
+    /// Only operators can read the system property that says, so for anyone else the version
+    /// stays unknown unless it's given to `VcoClientBuilder::assume_version`. Failing to find out
+    /// isn't fatal: it's only reported by `Client::version`.
+    async fn detect_version(&mut self) {
+        if self.version.is_some() || self.principal() != Some(Principal::Operator) {
+            return;
+        }
+        let method = "systemProperty/getSystemProperty";
+        let params = serde_json::json!({ "name": VERSION_PROPERTY });
+        match self
+            .post_with_payload::<GetSystemPropertiesResultItem>(method, &params)
+            .await
+        {
+            Ok(item) => match item.property.value.parse() {
+                Ok(version) => self.version = Some(version),
+                Err(e) => log::warn!("Couldn't tell VCO's version: {e}"),
+            },
+            Err(e) => log::warn!("Couldn't ask VCO its version: {e}"),
+        }
+    }
 
-        // Build client.
-        let client = client_builder
-            .build()
-            .map_err(ClientError::ReqwestClientCreate)?;
+    /// Find out whether VCO has API v2's resources, unless we've been told already, by asking for
+    /// the first page of its `enterprises` collection. Releases without API v2 don't have the
+    /// collection, so only a 404 or 405 means there's no API v2. Any other failure leaves it
+    /// unknown, and API v1 is used.
+    async fn detect_api_v2(&mut self) {
+        if self.api_v2.is_some() {
+            return;
+        }
+        let resource = format!("enterprises?{LIMIT_PARAM}=1");
+        match self
+            .call(Transport::RestV2, Verb::Get, &resource, None)
+            .await
+        {
+            Ok(_) => self.api_v2 = Some(true),
+            Err(ClientError::HttpStatus { status, .. })
+                if status == StatusCode::NOT_FOUND || status == StatusCode::METHOD_NOT_ALLOWED =>
+            {
+                log::debug!("Not using API v2, as VCO doesn't have it: HTTP status {status}");
+                self.api_v2 = Some(false);
+            }
+            Err(e) => log::warn!("Couldn't tell whether VCO has API v2, so not using it: {e}"),
+        }
+    }
+
+    /// Call a `get*User` method without an ID, which makes VCO return the caller.
+    async fn whoami(&self, method: &str) -> Result<User, ClientError> {
+        let user = self
+            .post_with_payload(method, &serde_json::json!({}))
+            .await
+            .map_err(ClientError::into_auth_failed)?;
+        Ok(user)
+    }
 
-        // TODO do something to confirm good token
+    /// Fail with `ClientError::OperatorRequired` unless we're logged in as an operator, so that
+    /// operator-only methods fail before asking VCO.
+    pub(crate) fn require_operator(&self, method: &str) -> Result<(), ClientError> {
+        match self.principal() {
+            Some(principal) if principal != Principal::Operator => {
+                Err(ClientError::OperatorRequired {
+                    method: method.into(),
+                    principal,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
 
-        Ok(Self {
+    pub(crate) fn new(client: reqwest::Client, base_url: Url) -> Self {
+        Self {
             client,
-            hostname,
-            domain,
+            base_url,
+            transport: Transport::default(),
+            retry_policy: RetryPolicy::never(),
+            throttle: Throttle::default(),
+            identity: None,
+            version: None,
+            api_v2: None,
+            next_request_id: AtomicU64::new(1),
+            credential_provider: None,
+            relogin_lock: Mutex::new(()),
+            session_generation: AtomicU64::new(0),
+            cookies: Arc::default(),
+            cassette: None,
+            field_audit: FieldAudit::Off,
+            unhandled_fields: std::sync::Mutex::default(),
+            deserialize_profile: DeserializeProfile::Strict,
+        }
+    }
+
+    //
+    // CONFIGURATION
+    //
+
+    /// Use the given `Transport` for all subsequent API v1 calls.
+    ///
+    /// Logging in always happens over REST; the transport only applies to calls made afterwards.
+    /// API v2 resources are always fetched over `Transport::RestV2`.
+    pub fn with_transport(mut self, transport: Transport) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// The user this client is logged in as.
+    pub fn identity(&self) -> Option<&Identity> {
+        self.identity.as_ref()
+    }
+
+    /// The kind of user this client is logged in as.
+    pub fn principal(&self) -> Option<Principal> {
+        self.identity.as_ref().map(|identity| identity.principal)
+    }
+
+    /// The release of VCO this client is talking to, if it's known.
+    pub fn version(&self) -> Option<VcoVersion> {
+        self.version
+    }
+
+    /// Take VCO to be release `version`, rather than asking it, e.g. when logged in as someone
+    /// who can't ask.
+    pub fn with_version(mut self, version: VcoVersion) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Take VCO to have API v2's resources, or not, rather than finding out when logging in.
+    pub fn with_api_v2(mut self, available: bool) -> Self {
+        self.api_v2 = Some(available);
+        self
+    }
+
+    /// Whether API v2's resources are used rather than API v1's methods. That's when VCO was
+    /// found to have them when logging in, or `with_api_v2` said so.
+    pub fn uses_api_v2(&self) -> bool {
+        self.api_v2 == Some(true)
+    }
+
+    /// The `Transport` used for API calls.
+    pub fn transport(&self) -> Transport {
+        self.transport
+    }
+
+    /// Retry transiently-failing API calls according to `retry_policy`.
+    ///
+    /// By default calls are not retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// The `RetryPolicy` used for API calls.
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Pace all subsequent API calls with `throttle`, e.g.
+    /// `Throttle::builder().rate_limit(10.0, 20).max_in_flight(8).build()?`.
+    ///
+    /// By default calls are not paced.
+    pub fn with_throttle(mut self, throttle: Throttle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// The `Throttle` used for API calls. Clone it to share its limits with another `Client`.
+    pub fn throttle(&self) -> &Throttle {
+        &self.throttle
+    }
+
+    /// When a password-authenticated session expires, log in again once with the credentials
+    /// from `credential_provider` and replay the call that failed.
+    ///
+    /// By default an expired session is an error.
+    pub fn with_credential_provider(mut self, credential_provider: CredentialProvider) -> Self {
+        self.credential_provider = Some(credential_provider);
+        self
+    }
+
+    /// Record all subsequent API calls to `cassette`, or replay them from it, depending on its
+    /// mode.
+    pub fn with_cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Deserialize responses with `profile`, e.g. `DeserializeProfile::Lenient` to accept
+    /// `true` where VCO used to send `1`. See `api_v1::profile`.
+    ///
+    /// By default responses are deserialized strictly.
+    pub fn with_deserialize_profile(mut self, profile: DeserializeProfile) -> Self {
+        self.deserialize_profile = profile;
+        self
+    }
+
+    /// The `DeserializeProfile` responses are deserialized with.
+    pub fn deserialize_profile(&self) -> DeserializeProfile {
+        self.deserialize_profile
+    }
+
+    /// Check responses for fields that `api_v1` doesn't model, as `field_audit` says.
+    ///
+    /// By default they're kept in the `unhandled_fields` of the structs returned, but not looked
+    /// for.
+    pub fn with_field_audit(mut self, field_audit: FieldAudit) -> Self {
+        self.field_audit = field_audit;
+        self
+    }
+
+    /// The fields that `api_v1` doesn't model found in responses so far, if there's a
+    /// `FieldAudit`.
+    pub fn unhandled_field_report(&self) -> UnhandledFieldReport {
+        self.unhandled_fields.lock().unwrap().clone()
+    }
+
+    /// Look for unhandled fields in `response` to `method`, according to the `FieldAudit`.
+    fn audit(&self, method: &str, response: &impl UnhandledFields) {
+        if self.field_audit == FieldAudit::Off {
+            return;
+        }
+        let mut report = self.unhandled_fields.lock().unwrap();
+        response.visit_unhandled("", &mut |path, _| {
+            if report.add(method, path) && self.field_audit == FieldAudit::Log {
+                log::warn!("{method} returned {path}, which isn't modeled");
+            }
+        });
+    }
+
+    //
+    // SESSION
+    //
+
+    /// Log out, ending the session on VCO. Calls made afterwards fail with an auth error, unless
+    /// there's a credential provider to log in again with.
+    pub async fn logout(&self) -> Result<(), ClientError> {
+        self.exchange(Transport::Rest, Verb::Post, "logout", None)
+            .await?;
+        Ok(())
+    }
+
+    /// The current password-authenticated session, for saving and resuming later with
+    /// `VcoClientBuilder::resume_session`. `None` if we logged in with a token.
+    pub fn session(&self) -> Option<Session> {
+        let identity = self.identity.as_ref()?;
+        let cookie = self.cookies.session_cookie()?;
+        Some(Session {
+            base_url: self.base_url.to_string(),
+            username: identity.username.clone(),
+            principal: identity.principal,
+            cookie: cookie.value,
+            expires: cookie.expires,
         })
     }
 
+    /// The login method for the kind of user we are.
+    fn login_method(&self) -> &'static str {
+        match self.principal() {
+            None | Some(Principal::Operator) => "login/operatorLogin",
+            Some(_) => "login/enterpriseLogin",
+        }
+    }
+
+    /// Log in again with the credential provider's credentials, unless another call already has
+    /// since `generation`.
+    async fn relogin(&self, generation: u64) -> Result<(), ClientError> {
+        let _guard = self.relogin_lock.lock().await;
+        if self.session_generation.load(Ordering::Acquire) != generation {
+            return Ok(());
+        }
+
+        let credentials = match &self.credential_provider {
+            Some(provider) => provider.credentials_blocking().await?,
+            None => None,
+        }
+        .ok_or_else(|| ClientError::AuthFailed("No credentials to log in again with".into()))?;
+        let auth_object = AuthObject::new(credentials.username, credentials.password);
+        let params = serde_json::to_value(&auth_object).map_err(ClientError::Json)?;
+        self.exchange(
+            Transport::Rest,
+            Verb::Post,
+            self.login_method(),
+            Some(params),
+        )
+        .await
+        .map_err(ClientError::into_auth_failed)?;
+
+        self.session_generation.fetch_add(1, Ordering::AcqRel);
+        Ok(())
+    }
+
     //
-    // REST API CALLS
+    // API CALLS
     //
 
-    /// The general-case POST request.
+    /// The general-case POST request, sent using the client's `Transport`.
     /// Users should use the `post-with-payload` and `post-without-payload` calls instead.
     /// If the response is empty then it will try to vivify `T` from `null`.
+    ///
+    /// Calls that fail transiently are retried according to the client's `RetryPolicy`. If the
+    /// session has expired and there's a credential provider, we log in again and replay the call
+    /// once. Calls VCO says it has no method for fail with `ClientError::Unsupported`. The
+    /// response is audited according to the client's `FieldAudit`.
     async fn post<'de, T>(
         &self,
         path: &str,
         payload: Option<&impl Serialize>,
     ) -> Result<T, ClientError>
     where
-        T: serde::Deserialize<'de>,
+        T: serde::Deserialize<'de> + UnhandledFields,
+    {
+        let method = path.trim_start_matches('/');
+        let params = payload
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(ClientError::Json)?;
+
+        let json = self
+            .call(self.transport.for_api_v1(), Verb::Post, method, params)
+            .await
+            .map_err(|e| e.into_unsupported(method))?;
+        let response = with_profile(self.deserialize_profile, || T::deserialize(json))
+            .map_err(ClientError::Json)?;
+        self.audit(method, &response);
+        Ok(response)
+    }
+
+    /// Make an API v2 `verb` request of `resource`, e.g. `enterprises/{logicalId}`, with
+    /// `payload` as the body if there is one. Retries, logging in again and auditing are as for
+    /// `post`, with the response audited as e.g. `GET enterprises/{logicalId}`, without any query.
+    pub(crate) async fn request_v2<'de, T>(
+        &self,
+        verb: Verb,
+        resource: &str,
+        payload: Option<&impl Serialize>,
+    ) -> Result<T, ClientError>
+    where
+        T: Deserialize<'de> + UnhandledFields,
+    {
+        let params = payload
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(ClientError::Json)?;
+
+        let json = self.call(Transport::RestV2, verb, resource, params).await?;
+        let response = with_profile(self.deserialize_profile, || T::deserialize(json))
+            .map_err(ClientError::Json)?;
+        let path = resource.split('?').next().unwrap_or_default();
+        let method = format!("{} {path}", reqwest::Method::from(verb));
+        self.audit(&method, &response);
+        Ok(response)
+    }
+
+    /// Fetch every page of the API v2 collection `resource`, e.g. `enterprises`.
+    pub(crate) async fn get_all_v2<T>(&self, resource: &str) -> Result<Vec<T>, ClientError>
+    where
+        T: DeserializeOwned + UnhandledFields,
     {
-        // Start building a POST request.
-        let mut resp_builder = self.client.post(self.rest_api_url(path));
+        let mut items = vec![];
+        let mut next_page: Option<String> = None;
+        // A server that hands out a cursor twice would keep us going round forever.
+        let mut seen_pages = HashSet::new();
+        loop {
+            let mut query = url::form_urlencoded::Serializer::new(String::new());
+            query.append_pair(LIMIT_PARAM, &PAGE_LIMIT.to_string());
+            if let Some(next_page) = &next_page {
+                query.append_pair(NEXT_PAGE_PARAM, next_page);
+            }
+            let page: Page<T> = self
+                .request_v2(
+                    Verb::Get,
+                    &format!("{resource}?{}", query.finish()),
+                    None::<&serde_json::Value>,
+                )
+                .await?;
+
+            next_page = page.next_page().map(String::from);
+            items.extend(page.data);
+            match &next_page {
+                None => return Ok(items),
+                Some(cursor) if !seen_pages.insert(cursor.clone()) => {
+                    return Err(ClientError::BadResponse(format!(
+                        "{resource} gave the page cursor {cursor:?} more than once"
+                    )))
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    /// Delete the API v2 resource `resource`, e.g. `enterprises/{logicalId}/edges/{logicalId}`,
+    /// failing with `ClientError::NotFound` if there's no such thing. Whatever body VCO answers
+    /// with is ignored. `what` is the thing being deleted, e.g. "edge 12".
+    pub(crate) async fn delete_v2(
+        &self,
+        resource: &str,
+        what: impl FnOnce() -> String,
+    ) -> Result<(), ClientError> {
+        match self
+            .call(Transport::RestV2, Verb::Delete, resource, None)
+            .await
+        {
+            Err(ClientError::HttpStatus { status, .. }) if status == StatusCode::NOT_FOUND => {
+                Err(ClientError::NotFound {
+                    method: format!("DELETE {resource}"),
+                    what: what(),
+                })
+            }
+            result => result.map(|_| ()),
+        }
+    }
+
+    /// Call the API. If the session has expired and there's a credential provider, log in again
+    /// and replay the call once.
+    async fn call(
+        &self,
+        transport: Transport,
+        verb: Verb,
+        method: &str,
+        params: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value, ClientError> {
+        let generation = self.session_generation.load(Ordering::Acquire);
+        match self
+            .call_with_retries(transport, verb, method, &params)
+            .await
+        {
+            Err(e)
+                if e.is_session_expired()
+                    && self.credential_provider.is_some()
+                    && !method.starts_with("login/") =>
+            {
+                self.relogin(generation).await?;
+                self.call_with_retries(transport, verb, method, &params)
+                    .await
+            }
+            result => result,
+        }
+    }
+
+    /// Call the API, retrying according to the client's `RetryPolicy`.
+    async fn call_with_retries(
+        &self,
+        transport: Transport,
+        verb: Verb,
+        method: &str,
+        params: &Option<serde_json::Value>,
+    ) -> Result<serde_json::Value, ClientError> {
+        let mut attempt = 1;
+        loop {
+            match self.exchange(transport, verb, method, params.clone()).await {
+                Ok(json) => return Ok(json),
+                Err(e) if self.retry_policy.should_retry(verb, method, attempt, &e) => {
+                    tokio::time::sleep(self.retry_policy.delay(attempt, e.retry_after())).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Make a single `verb` call to the API over `transport`, returning the result unwrapped from
+    /// the transport's envelope, or the error.
+    ///
+    /// With a cassette the call is recorded, or replayed without going anywhere near VCO.
+    async fn exchange(
+        &self,
+        transport: Transport,
+        verb: Verb,
+        method: &str,
+        params: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value, ClientError> {
+        let RawResponse {
+            status,
+            retry_after,
+            text,
+        } = match &self.cassette {
+            Some(cassette) if cassette.mode() == CassetteMode::Replay => {
+                cassette.play_call(transport, verb, method, params)?
+            }
+            Some(cassette) => {
+                let response = self.send(transport, verb, method, params.clone()).await?;
+                // The call has been made, so a cassette that can't be written mustn't fail it.
+                if let Err(e) = cassette.record_call(transport, verb, method, params, &response) {
+                    log::warn!("Couldn't record {method} to the cassette: {e}");
+                }
+                response
+            }
+            None => self.send(transport, verb, method, params).await?,
+        };
+
+        // Unwrap the result from the transport's envelope, surfacing any API error.
+        // If the response is empty this gives `null`.
+        match transport.decode(&text) {
+            // An API error is the most useful thing to report, whatever the HTTP status.
+            Err(e @ ClientError::Api(_)) => Err(e),
+            _ if !status.is_success() => Err(ClientError::HttpStatus {
+                status,
+                retry_after,
+                body: text,
+            }),
+            result => result,
+        }
+    }
+
+    /// Send a `verb` call to VCO over `transport`, and read the response.
+    async fn send(
+        &self,
+        transport: Transport,
+        verb: Verb,
+        method: &str,
+        params: Option<serde_json::Value>,
+    ) -> Result<RawResponse, ClientError> {
+        // Wait for our turn, and hold on to it until we've got the response.
+        let _permit = self.throttle.acquire().await;
+
+        // Start building the request.
+        let mut resp_builder = self
+            .client
+            .request(verb.into(), self.api_url(&transport.url_path(method)));
 
         // Attach the payload if there is one.
-        if let Some(payload) = payload {
-            let raw = serde_json::to_string(&payload).map_err(ClientError::Json)?;
+        let id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        if let Some(raw) = transport.encode(method, params, id)? {
             resp_builder = resp_builder.body(raw);
         }
 
         // Send the request and await the response.
         // If we get an error before we get a response, surface it to the caller now.
         let resp = resp_builder.send().await.map_err(ClientError::Request)?;
+        let status = resp.status();
+        let retry_after = resp
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_retry_after);
 
         // Read the body text of the response.
         // NOTE: We're trusting VCO not to send back an unreasonably-sized body here.
         let text = resp.text().await.map_err(ClientError::Response)?;
 
-        // If the response is empty, try to vivify T from `null`.
-        if text.is_empty() {
-            return Ok(T::deserialize(serde_json::Value::Null).map_err(ClientError::Json)?);
-        }
-
-        // Interpret the body of the response as JSON.
-        let json: Value = serde_json::from_str(&text).map_err(ClientError::Json)?;
-
-        // Check the response to see if it's an error and respond accordingly.
-        if let Some(text) = Self::identify_error_body(&json) {
-            Err(ClientError::Api(text.to_string()))
-        } else {
-            Ok(T::deserialize(json).map_err(ClientError::Json)?)
-        }
+        Ok(RawResponse {
+            status,
+            retry_after,
+            text,
+        })
     }
 
     pub(crate) async fn post_without_payload<'de, T>(&self, path: &str) -> Result<T, ClientError>
     where
-        T: serde::Deserialize<'de>,
+        T: serde::Deserialize<'de> + UnhandledFields,
     {
         let payload: Option<&serde_json::Value> = None; // keep type-checking happy
         self.post(path, payload).await
@@ -151,35 +717,48 @@ impl Client {
         payload: &impl Serialize,
     ) -> Result<T, ClientError>
     where
-        T: Deserialize<'de>,
+        T: Deserialize<'de> + UnhandledFields,
     {
         self.post(path, Some(payload)).await
     }
 
+    /// Call a method that changes rows, failing with `ClientError::NotFound` if it changed none.
+    /// VCO doesn't give an error for changing something that doesn't exist, it just changes
+    /// nothing. `what` is the thing being changed, e.g. "gateway 12".
+    pub(crate) async fn post_to_change(
+        &self,
+        path: &str,
+        payload: &impl Serialize,
+        what: impl FnOnce() -> String,
+    ) -> Result<(), ClientError> {
+        let confirmation: RowsModifiedConfirmation = self.post_with_payload(path, payload).await?;
+        match confirmation.rows {
+            0 => Err(ClientError::NotFound {
+                method: path.into(),
+                what: what(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
     //
     // UTILITY METHODS
     //
 
     /// Check if we've received an API V1-style error body.
-    fn identify_error_body(json: &serde_json::Value) -> Option<String> {
+    ///
+    /// Anything in a top-level "error" key means the call failed, so an error that isn't shaped
+    /// like a VCO API error is still returned, as well as can be made out.
+    pub(crate) fn identify_error_body(json: &serde_json::Value) -> Option<ApiError> {
         // Error is always contained in a mapping, or "object" in JSON parlance.
         if let serde_json::Value::Object(top_level) = json {
             // Does the mapping contain an "error" key?
-            if let Some(error_value) = top_level.get("error") {
-                // Yes so try to match it to a VCO API error.
-                match <ApiError as Deserialize>::deserialize(error_value) {
-                    Ok(api_error) => {
-                        // It deserializes to an ApiError, so it must be a real error.
-                        Some(format!("{} ({})", api_error.message, api_error.code))
-                    }
-                    Err(_) => {
-                        // It doesn't deserialize as an `ApiError`, it must be something else.
-                        None
-                    }
-                }
-            } else {
-                // There's no "error" key in the top-level map, so this can't be an error.
-                None
+            match top_level.get("error") {
+                None | Some(serde_json::Value::Null) => None,
+                Some(error_value) => Some(
+                    <ApiError as Deserialize>::deserialize(error_value)
+                        .unwrap_or_else(|_| Self::unrecognized_error(error_value)),
+                ),
             }
         } else {
             // The top level isn't a mapping, which all error messages are.
@@ -187,13 +766,45 @@ impl Client {
         }
     }
 
-    /// Generate the headers map common to all calls.
-    fn common_client_headers(hostname: &str, domain: &str) -> HeaderMap {
-        let fqdn = format!("{hostname}.{domain}");
+    /// An `ApiError` made from an "error" value that isn't shaped like one, using its code and
+    /// message if it has them. The rest of it is kept in `the_rest`.
+    fn unrecognized_error(error_value: &serde_json::Value) -> ApiError {
+        let code = error_value
+            .get("code")
+            .and_then(serde_json::Value::as_i64)
+            .and_then(|code| i32::try_from(code).ok())
+            .unwrap_or(api_v1::error::SERVER_ERROR);
+        let message = match error_value.get("message") {
+            Some(serde_json::Value::String(message)) => message.clone(),
+            _ => match error_value {
+                serde_json::Value::String(message) => message.clone(),
+                _ => "Unrecognized error from VCO".into(),
+            },
+        };
+        let the_rest = match error_value {
+            serde_json::Value::Object(object) => object
+                .iter()
+                .filter(|(name, _)| !["code", "message"].contains(&name.as_str()))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+            _ => [("error".to_string(), error_value.clone())]
+                .into_iter()
+                .collect(),
+        };
+        ApiError {
+            code,
+            message,
+            data: None,
+            the_rest,
+        }
+    }
 
+    /// Generate the headers map common to all calls.
+    ///
+    /// `Host` is left for `reqwest` to fill in from the URL, so that it includes any port.
+    pub(crate) fn common_client_headers() -> HeaderMap {
         // Set up default headers.
         let mut default_headers = HeaderMap::new();
-        default_headers.insert(reqwest::header::HOST, fqdn.parse().unwrap());
         default_headers.insert(
             reqwest::header::CONTENT_TYPE,
             "application/json".parse().unwrap(),
@@ -202,44 +813,159 @@ impl Client {
         default_headers
     }
 
-    /// Generate the reqwests ClientBuilder used for all Client creates.
-    fn common_client_builder(default_headers: HeaderMap) -> ClientBuilder {
-        ClientBuilder::new()
-            .default_headers(default_headers)
-            .user_agent("vco-rs")
-            .cookie_store(true)
+    /// The base URL of the VCO, e.g. `https://vco01.example.com/`.
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
     }
 
-    /// Generate the FQDN from the hostname and domain.
+    /// Generate a URL for the given path on the VCO.
     #[inline]
-    fn fqdn(&self) -> String {
-        [self.hostname.as_str(), self.domain.as_str()].join(".")
+    pub(crate) fn api_url(&self, path: &str) -> Url {
+        // `base_url` ends in a `/` and `path` never starts with one, so this appends to it.
+        self.base_url
+            .join(path)
+            .unwrap_or_else(|e| panic!("Internal error: bad API path {path:?}: {e}"))
     }
+}
 
-    /// Generate a REST API URL.
-    #[inline]
-    pub(crate) fn rest_api_url(&self, path: &str) -> String {
-        format!(
-            "https://{fqdn}/{api_base}/{path}",
-            fqdn = self.fqdn(),
-            api_base = api_v1::API_BASE
-        )
+/// Make the base URL for the VCO called `fqdn`, e.g. `vco01.example.com` or
+/// `sdwan-orch01.corp:8443`.
+pub(crate) fn fqdn_to_base_url(fqdn: &str) -> Result<Url, ClientError> {
+    let fqdn = fqdn.trim();
+    if fqdn.is_empty() || fqdn.contains('/') {
+        return Err(ClientError::BadVcoFqdn(format!(
+            "Expected a host name, optionally with a port, not \"{fqdn}\"."
+        )));
     }
+    let url = Url::parse(&format!("https://{fqdn}/"))
+        .map_err(|e| ClientError::BadVcoFqdn(format!("Bad FQDN \"{fqdn}\": {e}.")))?;
+    normalize_base_url(url)
 }
 
-fn fqdn_to_name_and_domain(vco_fqdn: &str) -> Result<(String, String), ClientError> {
-    let parts = vco_fqdn.splitn(2, ".").collect::<Vec<&str>>();
-    if parts.len() != 2 {
-        return Err(ClientError::BadVcoFqdn(
-            "Bad FQDN format, expected at least one dot in name, not \"{vco_fqdn}\".".to_string(),
-        ));
+/// Check that `url` can be used as the base of API URLs, making sure it ends in a `/` so that any
+/// path prefix is kept when API paths are joined on.
+pub(crate) fn normalize_base_url(mut url: Url) -> Result<Url, ClientError> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(ClientError::BadBaseUrl(format!(
+            "Expected an http or https URL, not \"{url}\"."
+        )));
+    }
+    if url.host_str().is_none() {
+        return Err(ClientError::BadBaseUrl(format!(
+            "Expected a URL with a host, not \"{url}\"."
+        )));
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Err(ClientError::BadBaseUrl(format!(
+            "Base URL can't have a query or fragment: \"{url}\"."
+        )));
+    }
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+    Ok(url)
+}
+
+/// Check that the VCO's host name starts with "vco", as VCOs conventionally do.
+pub(crate) fn check_vco_name(url: &Url) -> Result<(), ClientError> {
+    let host = url.host_str().unwrap_or_default().to_lowercase();
+    if host.starts_with("vco") {
+        Ok(())
+    } else {
+        Err(ClientError::BadVcoFqdn(format!(
+            "VCO name must start with \"vco\"; got \"{host}\"."
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test_base_url {
+    use super::*;
+
+    fn client(base_url: &str) -> Client {
+        let url = normalize_base_url(Url::parse(base_url).unwrap()).unwrap();
+        Client::new(reqwest::Client::new(), url)
+    }
+
+    #[test]
+    fn test_fqdn_to_base_url() {
+        assert_eq!(
+            fqdn_to_base_url("vco01.example.com").unwrap().as_str(),
+            "https://vco01.example.com/"
+        );
+        assert_eq!(
+            fqdn_to_base_url("sdwan-orch01.corp:8443").unwrap().as_str(),
+            "https://sdwan-orch01.corp:8443/"
+        );
+        assert!(fqdn_to_base_url("https://vco01.example.com").is_err());
+        assert!(fqdn_to_base_url("").is_err());
+    }
+
+    #[test]
+    fn test_api_url() {
+        assert_eq!(
+            client("http://127.0.0.1:8080")
+                .api_url("portal/rest/network/getNetworkGateways")
+                .as_str(),
+            "http://127.0.0.1:8080/portal/rest/network/getNetworkGateways"
+        );
+        assert_eq!(
+            client("https://lab.example.com/vco1")
+                .api_url("portal/")
+                .as_str(),
+            "https://lab.example.com/vco1/portal/"
+        );
     }
-    let vco_name = parts[0].to_string().to_lowercase();
-    let vco_domain = parts[1].to_string().to_lowercase();
-    if !vco_name.starts_with("vco") {
-        return Err(ClientError::BadVcoFqdn(
-            "VCO name must start with \"vco\"; got \"{vco_fqdn}\".".to_string(),
+
+    #[test]
+    fn test_bad_base_urls() {
+        assert!(normalize_base_url(Url::parse("ftp://vco01.example.com").unwrap()).is_err());
+        assert!(normalize_base_url(Url::parse("https://vco01.example.com/?a=b").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_check_vco_name() {
+        assert!(check_vco_name(&fqdn_to_base_url("VCO01.example.com").unwrap()).is_ok());
+        assert!(check_vco_name(&fqdn_to_base_url("sdwan-orch01.corp").unwrap()).is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_principal {
+    use super::*;
+
+    fn client_as(principal: Principal) -> Client {
+        let user: User =
+            serde_json::from_value(serde_json::json!({"id": 1, "username": "user"})).unwrap();
+        let mut client = Client::new(
+            reqwest::Client::new(),
+            Url::parse("https://vco01.example.com/").unwrap(),
+        );
+        client.identity = Some(Identity::new(user, principal));
+        client
+    }
+
+    #[tokio::test]
+    async fn test_require_operator() {
+        let client = client_as(Principal::Operator);
+        assert!(client
+            .require_operator("network/getNetworkGateways")
+            .is_ok());
+
+        // Fails without going anywhere near the (non-existent) VCO.
+        let client = client_as(Principal::Enterprise);
+        let error = client.get_network_gateways().await.unwrap_err();
+        assert!(matches!(
+            error,
+            ClientError::OperatorRequired {
+                principal: Principal::Enterprise,
+                ..
+            }
         ));
+        assert_eq!(
+            error.to_string(),
+            "network/getNetworkGateways needs an operator login, but this client is logged in as enterprise"
+        );
     }
-    Ok((vco_name, vco_domain))
 }
@@ -0,0 +1,94 @@
+//! Credentials a `Client` can use to log in again when its session expires.
+
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use crate::error::ClientError;
+
+/// A username and password.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl Credentials {
+    pub fn new(username: &str, password: &str) -> Self {
+        Self {
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+}
+
+impl Debug for Credentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Credentials({}, ****)", self.username)
+    }
+}
+
+/// Called when a password-authenticated session expires, to get the credentials to log in again
+/// with. Returning `None` gives up, and the call that found the session expired fails.
+///
+/// The callback may block, e.g. to prompt the user: it's run on tokio's blocking thread pool, not
+/// on the task that found the session expired.
+#[derive(Clone)]
+pub struct CredentialProvider(Arc<dyn Fn() -> Option<Credentials> + Send + Sync>);
+
+impl CredentialProvider {
+    pub fn new(provider: impl Fn() -> Option<Credentials> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(provider))
+    }
+
+    /// Always log in again with the same `credentials`.
+    pub fn fixed(credentials: Credentials) -> Self {
+        Self::new(move || Some(credentials.clone()))
+    }
+
+    pub(crate) fn credentials(&self) -> Option<Credentials> {
+        (self.0)()
+    }
+
+    /// Call the callback on the blocking thread pool, so that it can't stall the runtime.
+    pub(crate) async fn credentials_blocking(&self) -> Result<Option<Credentials>, ClientError> {
+        let provider = self.clone();
+        tokio::task::spawn_blocking(move || provider.credentials())
+            .await
+            .map_err(|e| ClientError::AuthFailed(format!("Credential provider failed: {e}")))
+    }
+}
+
+impl Debug for CredentialProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CredentialProvider")
+    }
+}
+
+#[cfg(test)]
+mod test_credentials {
+    use super::*;
+
+    #[test]
+    fn test_password_not_in_debug() {
+        let provider = CredentialProvider::fixed(Credentials::new("alice", "hunter2"));
+        let credentials = provider.credentials().unwrap();
+        assert_eq!(credentials.password, "hunter2");
+        assert_eq!(format!("{credentials:?}"), "Credentials(alice, ****)");
+    }
+
+    #[tokio::test]
+    async fn test_credentials_blocking() {
+        let provider = CredentialProvider::new(|| {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            Some(Credentials::new("alice", "hunter2"))
+        });
+        let credentials = provider.credentials_blocking().await.unwrap().unwrap();
+        assert_eq!(credentials.username, "alice");
+
+        let provider = CredentialProvider::new(|| panic!("No terminal"));
+        assert!(matches!(
+            provider.credentials_blocking().await,
+            Err(ClientError::AuthFailed(_))
+        ));
+    }
+}
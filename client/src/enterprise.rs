@@ -0,0 +1,82 @@
+//! Client methods for working with enterprises (customers) and their edges (VCE).
+
+use serde_json::json;
+
+use api_v1::common::RowsModifiedConfirmation;
+use api_v1::edge::EdgeObject;
+
+use crate::client::*;
+use crate::error::*;
+use crate::model::{Edge, Enterprise};
+
+impl Client {
+    /// Get a list of all the enterprises on the network.
+    ///
+    /// When the client `uses_api_v2`, this uses API v2's `enterprises` collection instead.
+    pub async fn get_enterprises(&self) -> Result<Vec<Enterprise>, ClientError> {
+        self.require_operator("network/getNetworkEnterprises")?;
+        if self.uses_api_v2() {
+            let resp: Vec<api_v2::enterprise::Enterprise> = self.get_all_v2("enterprises").await?;
+            return Ok(resp.into_iter().map(Enterprise::from).collect());
+        }
+        let resp: Vec<api_v1::enterprise::Enterprise> = self
+            .post_without_payload("network/getNetworkEnterprises")
+            .await?;
+        Ok(resp.into_iter().map(Enterprise::from).collect())
+    }
+
+    /// Get a list of an enterprise's edges.
+    ///
+    /// When the client `uses_api_v2`, this uses API v2's `enterprises/{logicalId}/edges`
+    /// collection instead.
+    pub async fn get_enterprise_edges(
+        &self,
+        enterprise: &Enterprise,
+    ) -> Result<Vec<Edge>, ClientError> {
+        if self.uses_api_v2() {
+            let resource = format!("enterprises/{}/edges", enterprise.logical_id);
+            let resp: Vec<api_v2::edge::Edge> = self.get_all_v2(&resource).await?;
+            return Ok(resp.into_iter().map(Edge::from).collect());
+        }
+        let body = json!({"enterpriseId": enterprise.id});
+        let resp: Vec<EdgeObject> = self
+            .post_with_payload("enterprise/getEnterpriseEdges", &body)
+            .await?;
+        Ok(resp.into_iter().map(Edge::from).collect())
+    }
+
+    /// Delete one of an enterprise's edges. Fails with `ClientError::NotFound` if there's no such
+    /// edge.
+    ///
+    /// When the client `uses_api_v2`, this deletes it with API v2's
+    /// `enterprises/{logicalId}/edges/{logicalId}` instead.
+    pub async fn delete_edge(
+        &self,
+        enterprise: &Enterprise,
+        edge: &Edge,
+    ) -> Result<(), ClientError> {
+        let what = || format!("edge {}", edge.id);
+        if self.uses_api_v2() {
+            let resource = format!(
+                "enterprises/{}/edges/{}",
+                enterprise.logical_id, edge.logical_id
+            );
+            return self.delete_v2(&resource, what).await;
+        }
+        const METHOD: &str = "edge/deleteEdge";
+        let body = json!({"enterpriseId": enterprise.id, "id": edge.id});
+        let confirmations: Vec<RowsModifiedConfirmation> =
+            self.post_with_payload(METHOD, &body).await?;
+        // As with `post_to_change`, VCO changing nothing means there was nothing to delete.
+        if confirmations
+            .iter()
+            .all(|confirmation| confirmation.rows == 0)
+        {
+            return Err(ClientError::NotFound {
+                method: METHOD.into(),
+                what: what(),
+            });
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,12 @@
+use std::time::Duration;
+
 use reqwest::Error as RequestError;
-use serde::{Deserialize, Serialize};
+use reqwest::StatusCode;
 use thiserror::Error;
 
-use api_v1::Integer;
+use crate::identity::Principal;
+
+pub use api_v1::error::{Error, ErrorData, ErrorKind, ErrorValidationDetails};
 
 #[derive(Error, Debug)]
 #[non_exhaustive]
@@ -10,46 +14,258 @@ pub enum ClientError {
     #[error("Bad VCO FQDN: {0:?}")]
     BadVcoFqdn(String),
 
+    #[error("Bad VCO base URL: {0:?}")]
+    BadBaseUrl(String),
+
     #[error("Error making request: {0:?}")]
     Request(RequestError),
 
     #[error("Client create error: {0:?}")]
     ReqwestClientCreate(RequestError),
 
+    #[error("Bad throttle: {0}")]
+    BadThrottle(String),
+
+    #[error("Bad HTTP header: {0}")]
+    BadHeader(String),
+
+    #[error("Session cache error: {0}")]
+    SessionCache(#[source] std::io::Error),
+
+    #[error("Cassette error: {0}")]
+    CassetteIo(#[source] std::io::Error),
+
+    #[error("No recorded call to {method} with params {params} left in the cassette")]
+    CassetteUnmatched { method: String, params: String },
+
+    #[error("Authentication failed: {0}")]
+    AuthFailed(String),
+
+    #[error("{method} needs an operator login, but this client is logged in as {principal}")]
+    OperatorRequired {
+        method: String,
+        principal: Principal,
+    },
+
+    #[error("{method} isn't supported by this VCO")]
+    Unsupported { method: String },
+
+    #[error("{method} changed nothing: there's no {what}")]
+    NotFound { method: String, what: String },
+
+    #[error("Bad VCO version: {0:?}")]
+    BadVersion(String),
+
+    #[error("Bad certificate: {0}")]
+    BadCertificate(String),
+
     #[error("Response error: {0:?}")]
     Response(RequestError),
 
-    #[error("Error returned from API: {0:?}")]
-    Api(String),
+    #[error("Bad response: {0}")]
+    BadResponse(String),
+
+    #[error("Error returned from API: {0}")]
+    Api(Box<Error>),
+
+    #[error("Unexpected HTTP status {status}: {body:?}")]
+    HttpStatus {
+        status: StatusCode,
+        retry_after: Option<Duration>,
+        body: String,
+    },
 
     #[error("JSON error: {0:?}")]
     Json(serde_json::Error),
 }
 
-/// `Error`, `ErrorData` and `ErrorValidationDetails` are used to deserialize errors returned from
-/// the API.
-/// TODO: move these to `api_v1`.
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct Error {
-    pub code: Integer,
-    pub message: String,
-    #[serde(flatten)]
-    pub the_rest: serde_json::Map<String, serde_json::Value>,
-}
+impl ClientError {
+    /// Turn errors that mean VCO rejected our credentials into `AuthFailed`, leaving the rest
+    /// alone.
+    pub(crate) fn into_auth_failed(self) -> Self {
+        match &self {
+            ClientError::Api(error) if error.is_auth_error() => {
+                ClientError::AuthFailed(error.to_string())
+            }
+            ClientError::HttpStatus { status, .. }
+                if *status == StatusCode::UNAUTHORIZED || *status == StatusCode::FORBIDDEN =>
+            {
+                ClientError::AuthFailed(format!("HTTP status {status}"))
+            }
+            _ => self,
+        }
+    }
+
+    /// Turn VCO's answer that it has no such method as `method` into `Unsupported`, leaving
+    /// other errors alone.
+    pub(crate) fn into_unsupported(self, method: &str) -> Self {
+        match &self {
+            ClientError::Api(error) if error.kind() == ErrorKind::MethodNotFound => {
+                ClientError::Unsupported {
+                    method: method.into(),
+                }
+            }
+            _ => self,
+        }
+    }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct ErrorData {
-    pub valid: bool,
-    pub error: Option<ErrorValidationDetails>,
-    pub warn: Option<ErrorValidationDetails>,
+    /// The error returned by the API, if that's what this is.
+    pub fn api_error(&self) -> Option<&Error> {
+        match self {
+            ClientError::Api(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// The classification of the API error, if this is one.
+    pub fn api_error_kind(&self) -> Option<ErrorKind> {
+        self.api_error().map(Error::kind)
+    }
+
+    /// Did the API reject the credentials, token or session?
+    pub fn is_auth_error(&self) -> bool {
+        matches!(self, ClientError::AuthFailed(_))
+            || self.api_error().is_some_and(Error::is_auth_error)
+    }
+
+    /// Did VCO reject the call because our session has expired or been logged out? Logging in
+    /// again may fix it.
+    pub fn is_session_expired(&self) -> bool {
+        match self {
+            ClientError::Api(error) => error.is_auth_error(),
+            ClientError::HttpStatus { status, .. } => *status == StatusCode::UNAUTHORIZED,
+            _ => false,
+        }
+    }
+
+    /// Did the API reject the call's parameters?
+    pub fn is_validation_error(&self) -> bool {
+        self.api_error().is_some_and(Error::is_validation_error)
+    }
+
+    /// Did the call refer to something that doesn't exist?
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, ClientError::NotFound { .. })
+            || self.api_error().is_some_and(Error::is_not_found)
+    }
+
+    /// Is this a failure that might go away if the call is tried again: a connection problem, an
+    /// overloaded or unavailable VCO, or VCO throttling us?
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ClientError::Request(error) => !error.is_builder(),
+            ClientError::Response(_) => true,
+            ClientError::HttpStatus { status, .. } => matches!(
+                *status,
+                StatusCode::TOO_MANY_REQUESTS
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            ),
+            ClientError::Api(error) => error.is_rate_limited(),
+            _ => false,
+        }
+    }
+
+    /// How long VCO asked us to wait before trying again, if it did.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ClientError::HttpStatus { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// The paths of the fields that failed validation, if this is a validation error.
+    pub fn validation_paths(&self) -> Vec<&str> {
+        self.api_error()
+            .map(Error::validation_paths)
+            .unwrap_or_default()
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct ErrorValidationDetails {
-    pub code: String,
-    pub message: String,
-    pub path: String,
+#[cfg(test)]
+mod test_client_error {
+    use super::*;
+
+    #[test]
+    fn test_into_auth_failed() {
+        let error: Error = serde_json::from_value(serde_json::json!({
+            "code": -32000,
+            "message": "tokenError [expired token]"
+        }))
+        .unwrap();
+        let error = ClientError::Api(Box::new(error)).into_auth_failed();
+        assert!(matches!(error, ClientError::AuthFailed(_)));
+        assert!(error.is_auth_error());
+
+        let error = ClientError::HttpStatus {
+            status: StatusCode::UNAUTHORIZED,
+            retry_after: None,
+            body: String::new(),
+        }
+        .into_auth_failed();
+        assert!(matches!(error, ClientError::AuthFailed(_)));
+
+        let error = ClientError::HttpStatus {
+            status: StatusCode::BAD_GATEWAY,
+            retry_after: None,
+            body: String::new(),
+        }
+        .into_auth_failed();
+        assert!(matches!(error, ClientError::HttpStatus { .. }));
+    }
+
+    #[test]
+    fn test_into_unsupported() {
+        let error: Error = serde_json::from_value(serde_json::json!({
+            "code": -32601,
+            "message": "Method not found"
+        }))
+        .unwrap();
+        let error = ClientError::Api(Box::new(error)).into_unsupported("example/getNewThing");
+        assert!(matches!(error, ClientError::Unsupported { .. }));
+        assert_eq!(
+            error.to_string(),
+            "example/getNewThing isn't supported by this VCO"
+        );
+
+        let error = ClientError::AuthFailed("no".into()).into_unsupported("example/getNewThing");
+        assert!(matches!(error, ClientError::AuthFailed(_)));
+    }
+
+    #[test]
+    fn test_odd_error_data() {
+        // However VCO shapes an error's data, the call fails with the error's code and message.
+        for data in [
+            r#""in use""#,
+            r#"["a"]"#,
+            r#"{"error": [{"path": "name"}]}"#,
+        ] {
+            let body =
+                format!(r#"{{"error": {{"code": -32000, "message": "no", "data": {data}}}}}"#);
+            let error = crate::transport::Transport::Rest.decode(&body).unwrap_err();
+            let error = error.api_error().unwrap();
+            assert_eq!((error.code, error.message.as_str()), (-32000, "no"));
+        }
+    }
+
+    #[test]
+    fn test_is_session_expired() {
+        let error: Error = serde_json::from_value(serde_json::json!({
+            "code": -32000,
+            "message": "tokenError [expired session cookie]"
+        }))
+        .unwrap();
+        assert!(ClientError::Api(Box::new(error)).is_session_expired());
+
+        let error = ClientError::HttpStatus {
+            status: StatusCode::UNAUTHORIZED,
+            retry_after: None,
+            body: String::new(),
+        };
+        assert!(error.is_session_expired());
+
+        // Failing to log in isn't an expired session.
+        assert!(!ClientError::AuthFailed("bad password".into()).is_session_expired());
+    }
 }
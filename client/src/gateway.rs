@@ -1,51 +1,164 @@
 //! Client methods for working with Gateways (VCG).
 
-// TODO don't "go around" the client module.
-use api_v1::date_time::*;
-use api_v1::gateway::*;
-use api_v1::Integer;
+use serde_json::json;
+use time::OffsetDateTime;
+
+use api_v1::date_time::{DateTime, Interval};
+use api_v1::gateway::{
+    GatewayMetric, GatewayProvisionResult, GetGatewayStatusMetrics, GetGatewayStatusMetricsResult,
+    GetNetworkGateways, NetworkGetNetworkGatewaysResultItem, UpdateGatewayAttributes,
+};
 
 use crate::client::*;
 use crate::error::*;
+use crate::id::GatewayId;
+use crate::model::{
+    Gateway, GatewayAttributes, GatewayExpansion, GatewayMetricSeries, GatewayProvision, Metric,
+    NewGateway, ServiceState,
+};
 
 impl Client {
-    /// Get the status metrics for a VCG.
-    /// TODO accept and return types not coming directly from whichever underlying API crate.
+    /// Get the status metrics for a VCG from `start` to `end`, or to now if `end` is `None`.
+    ///
+    /// `Metric::ALL` asks for every metric there is.
     pub async fn get_gateway_status_metrics(
         &self,
-        gateway_id: Integer,
-        start: &DateTime,
-        end: Option<&DateTime>,
-        metrics: &[GatewayMetric],
-    ) -> Result<String, ClientError> {
+        gateway_id: GatewayId,
+        start: OffsetDateTime,
+        end: Option<OffsetDateTime>,
+        metrics: &[Metric],
+    ) -> Result<GatewayMetricSeries, ClientError> {
+        self.require_operator("metrics/getGatewayStatusMetrics")?;
         let body = GetGatewayStatusMetrics {
             gateway_id,
             interval: Interval {
-                end: end.map(DateTime::clone),
-                start: start.clone(),
+                end: end.map(DateTime::Stamp),
+                start: DateTime::Stamp(start),
             },
-            metrics: metrics.into(),
+            metrics: metrics.iter().copied().map(GatewayMetric::from).collect(),
         };
-        let body = serde_json::ser::to_string(&body).expect("Couldn't JSON serialize body");
-        println!("{}", serde_json::to_string_pretty(&body).unwrap());
-
-        let resp = self
-            .post_with_payload("/metrics/getGatewayStatusMetrics", &body)
+        let resp: GetGatewayStatusMetricsResult = self
+            .post_with_payload("metrics/getGatewayStatusMetrics", &body)
             .await?;
-        Ok(resp)
+        Ok(resp.into())
     }
 
     /// Get a list of all the network gateways, AKA "VCGs".
-    /// TODO return some type not coming directly from whichever underlying API crate.
-    /// TODO `/network/getNetworkGateways` allow passing in `with` params:
-    ///      `{"with":["site","roles","pools","dataCenters","certificates","enterprises",
-    ///                "handOffEdges","enterpriseAssociationCounts"]}`
-    pub async fn get_network_gateways(
+    ///
+    /// When the client `uses_api_v2`, this uses API v2's `gateways` collection instead.
+    pub async fn get_network_gateways(&self) -> Result<Vec<Gateway>, ClientError> {
+        self.get_network_gateways_with(&[]).await
+    }
+
+    /// Get a list of all the network gateways, with the extra details in `with` for each.
+    ///
+    /// API v2 can't give the extra details, so if any are asked for this uses API v1 whatever
+    /// VCO's version.
+    pub async fn get_network_gateways_with(
         &self,
-    ) -> Result<Vec<NetworkGetNetworkGatewaysResultItem>, ClientError> {
-        let resp = self
-            .post_without_payload("network/getNetworkGateways")
+        with: &[GatewayExpansion],
+    ) -> Result<Vec<Gateway>, ClientError> {
+        self.require_operator("network/getNetworkGateways")?;
+        if with.is_empty() && self.uses_api_v2() {
+            let resp: Vec<api_v2::gateway::Gateway> = self.get_all_v2("gateways").await?;
+            return Ok(resp.into_iter().map(Gateway::from).collect());
+        }
+        let body = GetNetworkGateways { with: with.into() };
+        let resp: Vec<NetworkGetNetworkGatewaysResultItem> = self
+            .post_with_payload("network/getNetworkGateways", &body)
             .await?;
-        Ok(resp)
+        Ok(resp.into_iter().map(Gateway::from).collect())
+    }
+
+    /// Get the gateway `gateway_id`, or `None` if there's no such gateway.
+    ///
+    /// API v1 has no method to get a single gateway, so this gets them all and picks it out.
+    pub async fn get_gateway(&self, gateway_id: GatewayId) -> Result<Option<Gateway>, ClientError> {
+        Ok(self
+            .get_network_gateways()
+            .await?
+            .into_iter()
+            .find(|gateway| gateway.id == gateway_id))
+    }
+
+    /// Get all the gateways with their certificates, each in its gateway's `certificates`.
+    pub async fn get_gateway_certificates(&self) -> Result<Vec<Gateway>, ClientError> {
+        self.get_network_gateways_with(&[GatewayExpansion::Certificates])
+            .await
+    }
+
+    /// Add a gateway to the network. It needs activating with the returned key before it can
+    /// be used.
+    pub async fn insert_gateway(
+        &self,
+        gateway: &GatewayProvision,
+    ) -> Result<NewGateway, ClientError> {
+        self.require_operator("gateway/gatewayProvision")?;
+        let body = api_v1::gateway::GatewayProvision::from(gateway.clone());
+        let resp: GatewayProvisionResult = self
+            .post_with_payload("gateway/gatewayProvision", &body)
+            .await?;
+        Ok(resp.into())
+    }
+
+    /// Change a gateway's attributes. Those that are `None` in `attributes` are left as they are.
+    pub async fn update_gateway_attributes(
+        &self,
+        gateway_id: GatewayId,
+        attributes: GatewayAttributes,
+    ) -> Result<(), ClientError> {
+        self.require_operator("gateway/updateGatewayAttributes")?;
+        let body = UpdateGatewayAttributes {
+            id: gateway_id,
+            attributes: attributes.into(),
+        };
+        self.post_to_change("gateway/updateGatewayAttributes", &body, || {
+            format!("gateway {gateway_id}")
+        })
+        .await
+    }
+
+    /// Delete a gateway from the network.
+    pub async fn delete_gateway(&self, gateway_id: GatewayId) -> Result<(), ClientError> {
+        self.require_operator("gateway/deleteGateway")?;
+        let body = json!({"id": gateway_id});
+        self.post_to_change("gateway/deleteGateway", &body, || {
+            format!("gateway {gateway_id}")
+        })
+        .await
+    }
+
+    /// Quiesce a gateway, so that it takes on no more edges but keeps serving the ones it has,
+    /// e.g. before taking it out of service for maintenance.
+    pub async fn quiesce_gateway(&self, gateway_id: GatewayId) -> Result<(), ClientError> {
+        self.set_gateway_service_state(gateway_id, ServiceState::Quiesced)
+            .await
+    }
+
+    /// Put a gateway in service, so that edges can use it.
+    pub async fn put_gateway_in_service(&self, gateway_id: GatewayId) -> Result<(), ClientError> {
+        self.set_gateway_service_state(gateway_id, ServiceState::InService)
+            .await
+    }
+
+    /// Take a gateway out of service. Its edges move to other gateways.
+    pub async fn put_gateway_out_of_service(
+        &self,
+        gateway_id: GatewayId,
+    ) -> Result<(), ClientError> {
+        self.set_gateway_service_state(gateway_id, ServiceState::OutOfService)
+            .await
+    }
+
+    async fn set_gateway_service_state(
+        &self,
+        gateway_id: GatewayId,
+        service_state: ServiceState,
+    ) -> Result<(), ClientError> {
+        let attributes = GatewayAttributes {
+            service_state: Some(service_state),
+            ..Default::default()
+        };
+        self.update_gateway_attributes(gateway_id, attributes).await
     }
 }
@@ -0,0 +1,130 @@
+//! Client methods for working with gateway pools.
+
+use serde_json::json;
+
+use api_v1::gateway::{
+    GatewayPoolMembership, InsertNetworkGatewayPoolResult, NetworkGetNetworkGatewayPoolsResultItem,
+    UpdateNetworkGatewayPoolAttributes,
+};
+
+use crate::client::*;
+use crate::error::*;
+use crate::id::{EnterpriseId, GatewayId, GatewayPoolId};
+use crate::model::{GatewayPool, GatewayPoolAttributes, GatewayPoolProvision};
+
+impl Client {
+    /// Get a list of all the gateway pools, with the gateways in each.
+    pub async fn get_gateway_pools(&self) -> Result<Vec<GatewayPool>, ClientError> {
+        self.require_operator("network/getNetworkGatewayPools")?;
+        let resp: Vec<NetworkGetNetworkGatewayPoolsResultItem> = self
+            .post_with_payload(
+                "network/getNetworkGatewayPools",
+                &json!({"with": ["gateways"]}),
+            )
+            .await?;
+        Ok(resp.into_iter().map(GatewayPool::from).collect())
+    }
+
+    /// Add a gateway pool to the network, returning its ID. It starts with no gateways.
+    pub async fn insert_gateway_pool(
+        &self,
+        pool: &GatewayPoolProvision,
+    ) -> Result<GatewayPoolId, ClientError> {
+        self.require_operator("network/insertNetworkGatewayPool")?;
+        let body = api_v1::gateway::InsertNetworkGatewayPool::from(pool.clone());
+        let resp: InsertNetworkGatewayPoolResult = self
+            .post_with_payload("network/insertNetworkGatewayPool", &body)
+            .await?;
+        Ok(resp.id)
+    }
+
+    /// Change a gateway pool's attributes. Those that are `None` in `attributes` are left as
+    /// they are.
+    pub async fn update_gateway_pool(
+        &self,
+        pool_id: GatewayPoolId,
+        attributes: GatewayPoolAttributes,
+    ) -> Result<(), ClientError> {
+        self.require_operator("network/updateNetworkGatewayPoolAttributes")?;
+        let body = UpdateNetworkGatewayPoolAttributes {
+            id: pool_id,
+            update: attributes.into(),
+        };
+        self.post_to_change("network/updateNetworkGatewayPoolAttributes", &body, || {
+            format!("gateway pool {pool_id}")
+        })
+        .await
+    }
+
+    /// Delete a gateway pool. VCO refuses if enterprises are still assigned to it.
+    pub async fn delete_gateway_pool(&self, pool_id: GatewayPoolId) -> Result<(), ClientError> {
+        self.require_operator("network/deleteNetworkGatewayPool")?;
+        let body = json!({"id": pool_id});
+        self.post_to_change("network/deleteNetworkGatewayPool", &body, || {
+            format!("gateway pool {pool_id}")
+        })
+        .await
+    }
+
+    /// Add a gateway to a pool. A gateway can be in more than one pool.
+    pub async fn add_gateway_to_pool(
+        &self,
+        pool_id: GatewayPoolId,
+        gateway_id: GatewayId,
+    ) -> Result<(), ClientError> {
+        self.require_operator("network/insertNetworkGatewayPoolGateway")?;
+        let body = GatewayPoolMembership {
+            gateway_pool_id: pool_id,
+            gateway_id,
+        };
+        self.post_to_change("network/insertNetworkGatewayPoolGateway", &body, || {
+            format!("gateway pool {pool_id}")
+        })
+        .await
+    }
+
+    /// Remove a gateway from a pool.
+    ///
+    /// VCO removes it by the ID of its membership of the pool, so this gets the pools first to
+    /// find that.
+    pub async fn remove_gateway_from_pool(
+        &self,
+        pool_id: GatewayPoolId,
+        gateway_id: GatewayId,
+    ) -> Result<(), ClientError> {
+        const METHOD: &str = "network/deleteNetworkGatewayPoolGateway";
+        self.require_operator(METHOD)?;
+        let what = || format!("gateway {gateway_id} in gateway pool {pool_id}");
+        let assoc_id = self
+            .get_gateway_pools()
+            .await?
+            .into_iter()
+            .filter(|pool| pool.id == pool_id)
+            .flat_map(|pool| pool.gateways.into_iter().flatten())
+            .find(|gateway| gateway.id == gateway_id)
+            .and_then(|gateway| gateway.gateway_pool_assoc_id);
+        let Some(assoc_id) = assoc_id else {
+            return Err(ClientError::NotFound {
+                method: METHOD.into(),
+                what: what(),
+            });
+        };
+        self.post_to_change(METHOD, &json!({"id": assoc_id}), what)
+            .await
+    }
+
+    /// Assign an enterprise to a gateway pool. Its edges are given gateways from that pool from
+    /// then on.
+    pub async fn set_enterprise_gateway_pool(
+        &self,
+        enterprise_id: EnterpriseId,
+        pool_id: GatewayPoolId,
+    ) -> Result<(), ClientError> {
+        self.require_operator("enterprise/updateEnterprise")?;
+        let body = json!({"id": enterprise_id, "_update": {"gatewayPoolId": pool_id}});
+        self.post_to_change("enterprise/updateEnterprise", &body, || {
+            format!("enterprise {enterprise_id}")
+        })
+        .await
+    }
+}
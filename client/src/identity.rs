@@ -0,0 +1,101 @@
+//! Who a `Client` is logged in as.
+
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use api_v1::id::{EnterpriseId, EnterpriseProxyId};
+use api_v1::user::User;
+use api_v1::Integer;
+
+/// The kind of user a `Client` is logged in as, which decides which API methods it may call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Principal {
+    /// An operator, logged in with `login/operatorLogin`. Can call anything its role allows.
+    Operator,
+    /// A partner (MSP) admin, logged in with `login/enterpriseLogin`, managing the enterprises
+    /// of one enterprise proxy.
+    Partner,
+    /// An enterprise admin, logged in with `login/enterpriseLogin`, confined to one enterprise.
+    Enterprise,
+}
+
+impl Display for Principal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Principal::Operator => "operator",
+            Principal::Partner => "partner",
+            Principal::Enterprise => "enterprise",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The user that a `Client`'s session or API token belongs to, as reported by VCO at login.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Identity {
+    pub id: Integer,
+    pub username: String,
+    pub email: Option<String>,
+    /// The name of the user's role, e.g. "Superuser" or "Standard Operator".
+    pub role: Option<String>,
+    /// The kind of user as VCO names it, e.g. "OPERATOR".
+    pub user_type: Option<String>,
+    pub principal: Principal,
+    /// The enterprise an enterprise admin belongs to.
+    pub enterprise_id: Option<EnterpriseId>,
+    /// The enterprise proxy a partner admin belongs to.
+    pub enterprise_proxy_id: Option<EnterpriseProxyId>,
+}
+
+impl Identity {
+    pub(crate) fn new(user: User, principal: Principal) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            role: user.role_name,
+            user_type: user.user_type,
+            principal,
+            enterprise_id: user.enterprise_id,
+            enterprise_proxy_id: user.enterprise_proxy_id,
+        }
+    }
+
+    /// Work out the principal of a user who logged in with `login/enterpriseLogin`: partner
+    /// admins belong to an enterprise proxy rather than an enterprise.
+    pub(crate) fn enterprise_login_principal(user: &User) -> Principal {
+        if user.enterprise_id.is_none() && user.enterprise_proxy_id.is_some() {
+            Principal::Partner
+        } else {
+            Principal::Enterprise
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_identity {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_enterprise_login_principal() {
+        let user: User =
+            serde_json::from_value(json!({"id": 7, "username": "msp", "enterpriseProxyId": 2}))
+                .unwrap();
+        assert_eq!(
+            Identity::enterprise_login_principal(&user),
+            Principal::Partner
+        );
+
+        let user: User = serde_json::from_value(
+            json!({"id": 8, "username": "admin", "enterpriseId": 12, "enterpriseProxyId": 2}),
+        )
+        .unwrap();
+        assert_eq!(
+            Identity::enterprise_login_principal(&user),
+            Principal::Enterprise
+        );
+    }
+}
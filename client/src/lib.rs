@@ -1,6 +1,23 @@
+pub mod audit;
+pub mod builder;
+pub mod cassette;
 pub mod client;
+pub mod credentials;
+pub mod enterprise;
 pub mod error;
 pub mod gateway;
+pub mod gateway_pool;
+pub mod identity;
+pub mod model;
+mod pin;
 pub mod properties;
+pub mod retry;
+pub mod session;
+pub mod throttle;
+pub mod transport;
+pub mod version;
+
+pub use api_v1::id;
+pub use reqwest::Url;
 
 pub type Map<K, V> = std::collections::BTreeMap<K, V>;
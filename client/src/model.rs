@@ -0,0 +1,171 @@
+//! The things a `Client` works with, independent of the version of VCO's API they came from.
+//!
+//! The types in `api_v1` follow the wire format closely, warts and all: date-times that may be
+//! "never", tinyints for booleans, and a struct per response shape. The types here are what the
+//! rest of the world should see instead. Each has `From` conversions from its `api_v1` and
+//! `api_v2` counterparts, so callers get the same thing whichever API VCO was asked with. What's
+//! sent to VCO goes the other way: the request types here convert into their `api_v1` bodies.
+//!
+//! IDs and logical IDs are the same whatever the API version, so they're used as they are.
+
+use time::OffsetDateTime;
+
+use serde::de::DeserializeOwned;
+
+use api_v1::date_time::DateTime;
+use api_v1::network_address::{Address, NetAddress};
+use api_v1::profile::{with_profile, DeserializeProfile};
+
+mod certificate;
+mod common;
+mod edge;
+mod enterprise;
+mod gateway;
+mod gateway_pool;
+mod handoff;
+mod metric;
+mod property;
+mod site;
+mod syslog;
+
+pub use api_v1::gateway::GatewayExpansion;
+pub use api_v1::logical_id::{LogicalId, LogicalIdKind};
+pub use certificate::{Certificate, CertificateDetails, SubjectAltName};
+pub use common::{ActivationState, ServiceState};
+pub use edge::{Edge, EdgeState};
+pub use enterprise::Enterprise;
+pub use gateway::{
+    ConnectedEdge, DataCenter, EnterpriseAssociation, EnterpriseAssociationCount, Gateway,
+    GatewayAttributes, GatewayProvision, GatewayRole, GatewayRoleType, GatewayState, GatewayType,
+    NewGateway,
+};
+pub use gateway_pool::{
+    GatewayPool, GatewayPoolAttributes, GatewayPoolProvision, HandoffType, PoolGateway,
+};
+pub use handoff::{HandoffDetail, HandoffSubnet, IcmpProbe, IcmpResponder, SubnetHandoffType};
+pub use metric::{GatewayMetricSeries, Metric, MetricPoint, MetricSeries, MetricSummary};
+pub use property::{PropertyValue, SystemProperty};
+pub use site::{PostalAddress, Site, SiteAttributes};
+pub use syslog::{SyslogCollector, SyslogFacility, SyslogProtocol, SyslogSettings, SyslogSeverity};
+
+/// A wire date-time as a timestamp, or `None` if it's missing or "never".
+fn timestamp(value: DateTime) -> Option<OffsetDateTime> {
+    match value {
+        DateTime::Stamp(stamp) => Some(stamp),
+        DateTime::None | DateTime::Never => None,
+    }
+}
+
+/// A wire network address, or `None` if it's unset or couldn't be parsed.
+fn address<T: NetAddress>(value: Option<Address<T>>) -> Option<T> {
+    match value {
+        Some(Address::Some(address)) => Some(address),
+        _ => None,
+    }
+}
+
+/// The name VCO gives an enum value, e.g. "IN_SERVICE".
+fn vco_name(value: &impl serde::Serialize) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        other => panic!("Internal error: enum didn't serialize to a name: {other:?}"),
+    }
+}
+
+/// The `api_v1` enum value VCO calls `name`. A name it doesn't know is its `Other` value.
+fn from_vco_name<T: DeserializeOwned>(name: &str) -> T {
+    with_profile(DeserializeProfile::Lenient, || {
+        serde_json::from_value(serde_json::Value::String(name.into()))
+    })
+    .unwrap_or_else(|e| panic!("Internal error: enum didn't deserialize from {name:?}: {e}"))
+}
+
+/// Define an enum of the states VCO reports for something, mapped to and from VCO's names for
+/// them, with an `Other` variant for any state this crate doesn't know. It converts to and from
+/// `api_v1`'s enum for it.
+macro_rules! vco_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident from $wire:ty {
+            $($(#[$variant_meta:meta])* $variant:ident = $vco:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// A state this crate doesn't know, by VCO's name for it.
+            Other(String),
+        }
+
+        impl $name {
+            /// The state VCO calls `name`.
+            pub fn from_vco_name(name: &str) -> Self {
+                match name {
+                    $($vco => Self::$variant,)*
+                    other => Self::Other(other.into()),
+                }
+            }
+
+            /// VCO's name for the state.
+            pub fn vco_name(&self) -> &str {
+                match self {
+                    $(Self::$variant => $vco,)*
+                    Self::Other(name) => name,
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.vco_name())
+            }
+        }
+
+        impl From<$wire> for $name {
+            fn from(value: $wire) -> Self {
+                Self::from_vco_name(&$crate::model::vco_name(&value))
+            }
+        }
+
+        impl From<$name> for $wire {
+            fn from(value: $name) -> Self {
+                $crate::model::from_vco_name(value.vco_name())
+            }
+        }
+    };
+}
+
+use vco_enum;
+
+#[cfg(test)]
+mod test_model {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(timestamp(DateTime::Never), None);
+        assert!(timestamp(DateTime::from_rfc3339("2023-06-18T12:00:00Z").unwrap()).is_some());
+        assert_eq!(address::<Ipv4Addr>(Some(Address::Unknown)), None);
+        assert_eq!(
+            address(Some(Address::Some(Ipv4Addr::LOCALHOST))),
+            Some(Ipv4Addr::LOCALHOST)
+        );
+
+        let state = ServiceState::from(api_v1::common::ServiceState::OutOfService);
+        assert_eq!(state, ServiceState::OutOfService);
+        assert_eq!(state.to_string(), "OUT_OF_SERVICE");
+        let state = ServiceState::from(api_v1::common::ServiceState::Other("HIBERNATING".into()));
+        assert_eq!(state, ServiceState::Other("HIBERNATING".into()));
+
+        let state = api_v1::common::ServiceState::from(ServiceState::Quiesced);
+        assert_eq!(state, api_v1::common::ServiceState::Quiesced);
+        let state = api_v1::common::ServiceState::from(ServiceState::Other("HIBERNATING".into()));
+        assert_eq!(
+            state,
+            api_v1::common::ServiceState::Other("HIBERNATING".into())
+        );
+    }
+}
@@ -0,0 +1,218 @@
+//! Gateways' certificates, and what's in them.
+
+use std::fmt::{Display, Formatter};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use time::OffsetDateTime;
+use x509_parser::extensions::GeneralName;
+use x509_parser::pem::parse_x509_pem;
+
+use super::timestamp;
+use crate::error::ClientError;
+use crate::id::{CertificateId, GatewayId};
+
+/// A gateway's certificate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Certificate {
+    pub id: CertificateId,
+    pub gateway_id: GatewayId,
+    /// Like "0f:3a:91:2c".
+    pub serial_number: String,
+    pub subject_key_id: String,
+    /// The SHA-1 fingerprint.
+    pub finger_print: String,
+    /// The SHA-256 fingerprint.
+    pub finger_print_256: String,
+    pub created: Option<OffsetDateTime>,
+    /// When VCO says the certificate is valid from.
+    pub valid_from: Option<OffsetDateTime>,
+    /// When VCO says the certificate is valid to.
+    pub valid_to: Option<OffsetDateTime>,
+    /// The certificate itself, PEM-encoded.
+    pub pem: String,
+    /// What the certificate says, or `None` if it couldn't be parsed.
+    pub details: Option<CertificateDetails>,
+}
+
+impl Certificate {
+    /// When the certificate expires: as it says itself if it could be parsed, otherwise as VCO
+    /// says.
+    pub fn expires(&self) -> Option<OffsetDateTime> {
+        self.details
+            .as_ref()
+            .map(|details| details.not_after)
+            .or(self.valid_to)
+    }
+
+    /// Whether the certificate will have expired by `when`. One whose expiry isn't known won't.
+    pub fn expires_by(&self, when: OffsetDateTime) -> bool {
+        self.expires().is_some_and(|expires| expires <= when)
+    }
+}
+
+impl From<api_v1::gateway::GatewayCertificate> for Certificate {
+    fn from(certificate: api_v1::gateway::GatewayCertificate) -> Self {
+        let details = match CertificateDetails::from_pem(&certificate.certificate) {
+            Ok(details) => Some(details),
+            Err(e) => {
+                log::warn!("Certificate {}: {e}", certificate.id);
+                None
+            }
+        };
+        Self {
+            id: certificate.id,
+            gateway_id: certificate.gateway_id,
+            serial_number: certificate.serial_number,
+            subject_key_id: certificate.subject_key_id,
+            finger_print: certificate.finger_print,
+            finger_print_256: certificate.finger_print_256,
+            created: timestamp(certificate.created),
+            valid_from: timestamp(certificate.valid_from),
+            valid_to: timestamp(certificate.valid_to),
+            pem: certificate.certificate,
+            details,
+        }
+    }
+}
+
+/// What a certificate says about itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateDetails {
+    /// Like "O=Example Networks, CN=vcg01.example.com".
+    pub subject: String,
+    pub issuer: String,
+    pub subject_alt_names: Vec<SubjectAltName>,
+    pub not_before: OffsetDateTime,
+    pub not_after: OffsetDateTime,
+}
+
+impl CertificateDetails {
+    /// Parse a PEM-encoded X.509 certificate.
+    pub fn from_pem(pem: &str) -> Result<Self, ClientError> {
+        let bad_certificate = |e: &dyn Display| ClientError::BadCertificate(e.to_string());
+        let (_, pem) = parse_x509_pem(pem.as_bytes()).map_err(|e| bad_certificate(&e))?;
+        let certificate = pem.parse_x509().map_err(|e| bad_certificate(&e))?;
+        let subject_alt_names = certificate
+            .subject_alternative_name()
+            .map_err(|e| bad_certificate(&e))?
+            .map(|extension| {
+                extension
+                    .value
+                    .general_names
+                    .iter()
+                    .filter_map(SubjectAltName::from_general_name)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(Self {
+            subject: certificate.subject().to_string(),
+            issuer: certificate.issuer().to_string(),
+            subject_alt_names,
+            not_before: certificate.validity().not_before.to_datetime(),
+            not_after: certificate.validity().not_after.to_datetime(),
+        })
+    }
+}
+
+/// Another name a certificate is for, besides its subject.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SubjectAltName {
+    Dns(String),
+    Ip(IpAddr),
+    Email(String),
+    Uri(String),
+}
+
+impl SubjectAltName {
+    /// The name, if it's a kind this crate knows.
+    fn from_general_name(name: &GeneralName) -> Option<Self> {
+        match name {
+            GeneralName::DNSName(name) => Some(Self::Dns(name.to_string())),
+            GeneralName::RFC822Name(name) => Some(Self::Email(name.to_string())),
+            GeneralName::URI(name) => Some(Self::Uri(name.to_string())),
+            GeneralName::IPAddress(octets) => match **octets {
+                [a, b, c, d] => Some(Self::Ip(Ipv4Addr::new(a, b, c, d).into())),
+                ref octets => <[u8; 16]>::try_from(octets)
+                    .ok()
+                    .map(|octets| Self::Ip(Ipv6Addr::from(octets).into())),
+            },
+            _ => None,
+        }
+    }
+}
+
+impl Display for SubjectAltName {
+    /// As OpenSSL shows them, e.g. "DNS:vcg01.example.com".
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Dns(name) => write!(f, "DNS:{name}"),
+            Self::Ip(address) => write!(f, "IP:{address}"),
+            Self::Email(address) => write!(f, "email:{address}"),
+            Self::Uri(uri) => write!(f, "URI:{uri}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_certificate {
+    use super::*;
+    use time::format_description::well_known::Rfc3339;
+
+    fn at(rfc3339: &str) -> OffsetDateTime {
+        OffsetDateTime::parse(rfc3339, &Rfc3339).unwrap()
+    }
+
+    /// The certificates in `mock_vco`'s fixture.
+    fn certificates() -> Vec<Certificate> {
+        let items: Vec<api_v1::gateway::GatewayCertificate> = serde_json::from_str(include_str!(
+            "../../../mock_vco/fixtures/gateway_certificates.json"
+        ))
+        .unwrap();
+        items.into_iter().map(Certificate::from).collect()
+    }
+
+    #[test]
+    fn test_from_pem() {
+        let certificates = certificates();
+        let details = certificates[0].details.as_ref().unwrap();
+        assert_eq!(
+            details.subject,
+            "O=Example Networks, CN=vcg01-lon.example.com"
+        );
+        assert_eq!(details.issuer, "O=Example Networks, CN=Example Gateway CA");
+        assert_eq!(
+            details.subject_alt_names,
+            [
+                SubjectAltName::Dns("vcg01-lon.example.com".into()),
+                SubjectAltName::Ip(Ipv4Addr::new(192, 0, 2, 10).into()),
+            ]
+        );
+        assert_eq!(details.subject_alt_names[1].to_string(), "IP:192.0.2.10");
+        assert_eq!(details.not_before, at("2023-01-01T00:00:00Z"));
+        assert_eq!(details.not_after, at("2033-01-01T00:00:00Z"));
+
+        assert!(matches!(
+            CertificateDetails::from_pem(
+                "-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----\n"
+            ),
+            Err(ClientError::BadCertificate(_))
+        ));
+        assert!(CertificateDetails::from_pem("").is_err());
+    }
+
+    #[test]
+    fn test_expiry() {
+        let mut certificates = certificates();
+        let when = at("2023-06-18T12:00:00Z");
+        assert!(!certificates[0].expires_by(when));
+        assert!(certificates[1].expires_by(when + time::Duration::days(30)));
+        assert!(!certificates[1].expires_by(when));
+
+        // Without the details VCO's word is taken for it, and without that it never expires.
+        certificates[1].details = None;
+        assert_eq!(certificates[1].expires(), certificates[1].valid_to);
+        certificates[1].valid_to = None;
+        assert!(!certificates[1].expires_by(OffsetDateTime::now_utc()));
+    }
+}
@@ -0,0 +1,23 @@
+//! States that edges and gateways share.
+
+use super::vco_enum;
+
+vco_enum!(
+    /// Whether an edge or gateway has been activated.
+    ActivationState from api_v1::common::ActivationState {
+        Unassigned = "UNASSIGNED",
+        Pending = "PENDING",
+        Activated = "ACTIVATED",
+        ReactivationPending = "REACTIVATION_PENDING",
+    }
+);
+
+vco_enum!(
+    /// Whether an edge or gateway is in service.
+    ServiceState from api_v1::common::ServiceState {
+        InService = "IN_SERVICE",
+        OutOfService = "OUT_OF_SERVICE",
+        PendingService = "PENDING_SERVICE",
+        Quiesced = "QUIESCED",
+    }
+);
@@ -0,0 +1,108 @@
+//! Edges (VCE).
+
+use time::OffsetDateTime;
+
+use api_v1::edge::EdgeObject;
+
+use super::{timestamp, vco_enum, ActivationState, LogicalId, ServiceState};
+use crate::id::{EdgeId, EnterpriseId, SiteId};
+
+vco_enum!(
+    /// How an edge is getting on.
+    EdgeState from api_v1::edge::EdgeState {
+        NeverActivated = "NEVER_ACTIVATED",
+        Degraded = "DEGRADED",
+        Offline = "OFFLINE",
+        Disabled = "DISABLED",
+        Expired = "EXPIRED",
+        Connected = "CONNECTED",
+    }
+);
+
+/// An edge, i.e. a VCE.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge {
+    pub id: EdgeId,
+    pub logical_id: LogicalId,
+    pub name: String,
+    pub description: Option<String>,
+    pub enterprise_id: EnterpriseId,
+    pub site_id: SiteId,
+    pub edge_state: EdgeState,
+    pub activation_state: ActivationState,
+    pub service_state: ServiceState,
+    pub model_number: String,
+    pub device_family: String,
+    pub serial_number: Option<String>,
+    pub self_mac_address: Option<String>,
+    pub software_version: String,
+    pub build_number: String,
+    pub is_live: bool,
+    pub alerts_enabled: bool,
+    pub created: Option<OffsetDateTime>,
+    pub modified: Option<OffsetDateTime>,
+    pub activation_time: Option<OffsetDateTime>,
+    pub last_contact: Option<OffsetDateTime>,
+    pub service_up_since: Option<OffsetDateTime>,
+    pub system_up_since: Option<OffsetDateTime>,
+}
+
+impl From<EdgeObject> for Edge {
+    fn from(edge: EdgeObject) -> Self {
+        Self {
+            id: edge.id,
+            logical_id: edge.logical_id,
+            name: edge.name,
+            description: edge.description,
+            enterprise_id: edge.enterprise_id,
+            site_id: edge.site_id,
+            edge_state: edge.edge_state.into(),
+            activation_state: edge.activation_state.into(),
+            service_state: edge.service_state.into(),
+            model_number: edge.model_number,
+            device_family: edge.device_family,
+            serial_number: edge.serial_number,
+            self_mac_address: edge.self_mac_address,
+            software_version: edge.software_version,
+            build_number: edge.build_number,
+            is_live: edge.is_live != 0,
+            alerts_enabled: edge.alerts_enabled.0,
+            created: timestamp(edge.created),
+            modified: timestamp(edge.modified),
+            activation_time: timestamp(edge.activation_time),
+            last_contact: timestamp(edge.last_contact),
+            service_up_since: timestamp(edge.service_up_since),
+            system_up_since: timestamp(edge.system_up_since),
+        }
+    }
+}
+
+impl From<api_v2::edge::Edge> for Edge {
+    fn from(edge: api_v2::edge::Edge) -> Self {
+        Self {
+            id: edge.id,
+            logical_id: edge.logical_id,
+            name: edge.name,
+            description: edge.description,
+            enterprise_id: edge.enterprise_id,
+            site_id: edge.site_id,
+            edge_state: EdgeState::from_vco_name(&edge.edge_state),
+            activation_state: ActivationState::from_vco_name(&edge.activation_state),
+            service_state: ServiceState::from_vco_name(&edge.service_state),
+            model_number: edge.model_number,
+            device_family: edge.device_family,
+            serial_number: edge.serial_number,
+            self_mac_address: edge.self_mac_address,
+            software_version: edge.software_version,
+            build_number: edge.build_number,
+            is_live: edge.is_live,
+            alerts_enabled: edge.alerts_enabled,
+            created: edge.created,
+            modified: edge.modified,
+            activation_time: edge.activation_time,
+            last_contact: edge.last_contact,
+            service_up_since: edge.service_up_since,
+            system_up_since: edge.system_up_since,
+        }
+    }
+}
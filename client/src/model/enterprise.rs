@@ -0,0 +1,79 @@
+//! Enterprises, i.e. customers.
+
+use time::OffsetDateTime;
+
+use super::{timestamp, LogicalId};
+use crate::id::{EnterpriseId, GatewayPoolId, NetworkId};
+
+/// An enterprise, i.e. a customer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enterprise {
+    pub id: EnterpriseId,
+    pub logical_id: LogicalId,
+    pub name: String,
+    pub description: Option<String>,
+    pub domain: Option<String>,
+    pub account_number: String,
+    pub network_id: NetworkId,
+    pub gateway_pool_id: GatewayPoolId,
+    pub alerts_enabled: bool,
+    pub operator_alerts_enabled: bool,
+    pub contact_name: Option<String>,
+    pub contact_email: Option<String>,
+    pub city: Option<String>,
+    pub country: Option<String>,
+    pub timezone: String,
+    pub locale: String,
+    pub created: Option<OffsetDateTime>,
+    pub modified: Option<OffsetDateTime>,
+}
+
+impl From<api_v1::enterprise::Enterprise> for Enterprise {
+    fn from(enterprise: api_v1::enterprise::Enterprise) -> Self {
+        Self {
+            id: enterprise.id,
+            logical_id: enterprise.logical_id,
+            name: enterprise.name,
+            description: enterprise.description,
+            domain: enterprise.domain,
+            account_number: enterprise.account_number,
+            network_id: enterprise.network_id,
+            gateway_pool_id: enterprise.gateway_pool_id,
+            alerts_enabled: enterprise.alerts_enabled.0,
+            operator_alerts_enabled: enterprise.operator_alerts_enabled.0,
+            contact_name: enterprise.contact_name,
+            contact_email: enterprise.contact_email,
+            city: enterprise.city,
+            country: enterprise.country,
+            timezone: enterprise.timezone,
+            locale: enterprise.locale,
+            created: timestamp(enterprise.created),
+            modified: timestamp(enterprise.modified),
+        }
+    }
+}
+
+impl From<api_v2::enterprise::Enterprise> for Enterprise {
+    fn from(enterprise: api_v2::enterprise::Enterprise) -> Self {
+        Self {
+            id: enterprise.id,
+            logical_id: enterprise.logical_id,
+            name: enterprise.name,
+            description: enterprise.description,
+            domain: enterprise.domain,
+            account_number: enterprise.account_number,
+            network_id: enterprise.network_id,
+            gateway_pool_id: enterprise.gateway_pool_id,
+            alerts_enabled: enterprise.alerts_enabled,
+            operator_alerts_enabled: enterprise.operator_alerts_enabled,
+            contact_name: enterprise.contact_name,
+            contact_email: enterprise.contact_email,
+            city: enterprise.city,
+            country: enterprise.country,
+            timezone: enterprise.timezone,
+            locale: enterprise.locale,
+            created: enterprise.created,
+            modified: enterprise.modified,
+        }
+    }
+}
@@ -0,0 +1,536 @@
+//! Gateways (VCG).
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use time::OffsetDateTime;
+
+use api_v1::gateway::NetworkGetNetworkGatewaysResultItem;
+
+use super::{
+    address, timestamp, vco_enum, ActivationState, Certificate, Edge, Enterprise, GatewayPool,
+    HandoffDetail, LogicalId, ServiceState, Site, SiteAttributes, SyslogSettings,
+};
+use crate::id::{
+    DataCenterId, EdgeId, EnterpriseProxyId, GatewayId, GatewayPoolId, NetworkId, SiteId,
+};
+
+vco_enum!(
+    /// How a gateway is getting on.
+    GatewayState from api_v1::gateway::GatewayState {
+        NeverActivated = "NEVER_ACTIVATED",
+        Degraded = "DEGRADED",
+        Quiesced = "QUIESCED",
+        Disabled = "DISABLED",
+        OutOfService = "OUT_OF_SERVICE",
+        Connected = "CONNECTED",
+        Offline = "OFFLINE",
+    }
+);
+
+vco_enum!(
+    /// A part a gateway plays.
+    GatewayRoleType from api_v1::gateway::GatewayRoleType {
+        DataPlane = "DATA_PLANE",
+        ControlPlane = "CONTROL_PLANE",
+        VpnTunnel = "VPN_TUNNEL",
+        OnPremise = "ON_PREMISE",
+        Cde = "CDE",
+        Cws = "CWS",
+    }
+);
+
+vco_enum!(
+    /// How an enterprise uses a gateway.
+    GatewayType from api_v1::gateway::GatewayType {
+        Super = "SUPER",
+        SuperAlt = "SUPER_ALT",
+        Datacenter = "DATACENTER",
+        Handoff = "HANDOFF",
+        Primary = "PRIMARY",
+        Secondary = "SECONDARY",
+        /// VCO's own `OTHER` type, not one this crate doesn't know.
+        OtherType = "OTHER",
+    }
+);
+
+/// A gateway, i.e. a VCG.
+///
+/// The details a `GatewayExpansion` asks for are `None` unless they were asked for. API v2
+/// doesn't give them, nor `connected_edge_list`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gateway {
+    pub id: GatewayId,
+    /// Like "gateway01234567-89ab-cdef-0123-456789abcdef".
+    pub logical_id: LogicalId,
+    pub name: String,
+    pub description: Option<String>,
+    pub dns_name: Option<String>,
+    pub network_id: Option<NetworkId>,
+    pub enterprise_proxy_id: Option<EnterpriseProxyId>,
+    pub site_id: SiteId,
+    pub software_version: String,
+    pub build_number: String,
+    pub device_id: Option<String>,
+    pub ip_address: Option<Ipv4Addr>,
+    pub ip_v6_address: Option<Ipv6Addr>,
+    pub private_ip_address: Option<Ipv4Addr>,
+    pub gateway_state: GatewayState,
+    pub activation_state: ActivationState,
+    pub service_state: ServiceState,
+    /// How busy the gateway is, as a percentage.
+    pub utilization: f32,
+    pub connected_edges: i32,
+    /// The edges connected to the gateway, if VCO said.
+    pub connected_edge_list: Option<Vec<ConnectedEdge>>,
+    pub is_load_balanced: bool,
+    pub alerts_enabled: Option<bool>,
+    pub created: Option<OffsetDateTime>,
+    pub modified: Option<OffsetDateTime>,
+    pub activation_time: Option<OffsetDateTime>,
+    pub last_contact: Option<OffsetDateTime>,
+    pub service_up_since: Option<OffsetDateTime>,
+    pub system_up_since: Option<OffsetDateTime>,
+    /// Where the gateway is.
+    pub site: Option<Site>,
+    /// The parts the gateway plays.
+    pub roles: Option<Vec<GatewayRole>>,
+    /// The enterprises using the gateway.
+    pub enterprises: Option<Vec<Enterprise>>,
+    /// How each enterprise uses the gateway, and through which edge.
+    pub enterprise_associations: Option<Vec<EnterpriseAssociation>>,
+    /// The edges the gateway hands off for.
+    pub hand_off_edges: Option<Vec<Edge>>,
+    /// The data centers the gateway is in.
+    pub data_centers: Option<Vec<DataCenter>>,
+    /// How much is associated with the gateway.
+    pub enterprise_association_count: Option<EnterpriseAssociationCount>,
+    /// The pools the gateway is in.
+    pub pools: Option<Vec<GatewayPool>>,
+    /// The gateway's certificates.
+    pub certificates: Option<Vec<Certificate>>,
+}
+
+/// A gateway just added to the network, which has yet to be activated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewGateway {
+    pub id: GatewayId,
+    pub logical_id: LogicalId,
+    /// The key to activate the gateway with.
+    pub activation_key: String,
+}
+
+impl From<api_v1::gateway::GatewayProvisionResult> for NewGateway {
+    fn from(result: api_v1::gateway::GatewayProvisionResult) -> Self {
+        Self {
+            id: result.id,
+            logical_id: result.logical_id,
+            activation_key: result.activation_key,
+        }
+    }
+}
+
+/// A gateway to add to a network.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GatewayProvision {
+    pub network_id: NetworkId,
+    pub name: String,
+    pub ip_address: Ipv4Addr,
+    pub description: Option<String>,
+    pub site: Option<SiteAttributes>,
+    /// The pool to put the gateway in.
+    pub gateway_pool_id: Option<GatewayPoolId>,
+    pub hand_off_detail: Option<HandoffDetail>,
+}
+
+impl From<GatewayProvision> for api_v1::gateway::GatewayProvision {
+    fn from(gateway: GatewayProvision) -> Self {
+        Self {
+            network_id: gateway.network_id,
+            name: gateway.name,
+            ip_address: gateway.ip_address,
+            description: gateway.description,
+            site: gateway.site.map(Into::into),
+            gateway_pool_id: gateway.gateway_pool_id,
+            hand_off_detail: gateway.hand_off_detail.map(Into::into),
+        }
+    }
+}
+
+/// Changes to a gateway's attributes. Those that are `None` are left as they are.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GatewayAttributes {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub ip_address: Option<Ipv4Addr>,
+    pub site: Option<SiteAttributes>,
+    pub hand_off_detail: Option<HandoffDetail>,
+    pub syslog: Option<SyslogSettings>,
+    pub service_state: Option<ServiceState>,
+}
+
+impl From<GatewayAttributes> for api_v1::gateway::GatewayAttributes {
+    fn from(attributes: GatewayAttributes) -> Self {
+        Self {
+            name: attributes.name,
+            description: attributes.description,
+            ip_address: attributes.ip_address,
+            site: attributes.site.map(Into::into),
+            hand_off_detail: attributes.hand_off_detail.map(Into::into),
+            syslog: attributes.syslog.map(Into::into),
+            service_state: attributes.service_state.map(Into::into),
+        }
+    }
+}
+
+/// A data center a gateway is in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataCenter {
+    pub id: DataCenterId,
+    pub logical_id: LogicalId,
+    pub name: String,
+}
+
+impl From<api_v1::gateway::DataCenter> for DataCenter {
+    fn from(data_center: api_v1::gateway::DataCenter) -> Self {
+        Self {
+            id: data_center.id,
+            logical_id: data_center.logical_id,
+            name: data_center.name,
+        }
+    }
+}
+
+/// How many enterprises, edges and profiles are associated with a gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnterpriseAssociationCount {
+    pub enterprises: i32,
+    pub edges: i32,
+    pub profiles: i32,
+}
+
+impl From<api_v1::gateway::EnterpriseAssociationCount> for EnterpriseAssociationCount {
+    fn from(count: api_v1::gateway::EnterpriseAssociationCount) -> Self {
+        Self {
+            enterprises: count.enterprise_count,
+            edges: count.edge_count,
+            profiles: count.profile_count,
+        }
+    }
+}
+
+/// A part a gateway plays.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GatewayRole {
+    pub role: GatewayRoleType,
+    /// Whether the gateway has to play it.
+    pub required: bool,
+}
+
+impl From<api_v1::gateway::GatewayRole> for GatewayRole {
+    fn from(role: api_v1::gateway::GatewayRole) -> Self {
+        Self {
+            role: role.gateway_role.into(),
+            required: role.required != 0,
+        }
+    }
+}
+
+/// How an enterprise uses a gateway.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnterpriseAssociation {
+    pub enterprise: Enterprise,
+    pub gateway_type: GatewayType,
+    /// The edge using the gateway, if it's used by one rather than by the whole enterprise.
+    pub edge_id: Option<EdgeId>,
+    pub edge_name: Option<String>,
+    pub edge_logical_id: Option<LogicalId>,
+    /// Whether the gateway was chosen for the edge by hand, rather than by VCO.
+    pub pinned: bool,
+}
+
+impl From<api_v1::gateway::GatewayEnterpriseAssoc> for EnterpriseAssociation {
+    fn from(association: api_v1::gateway::GatewayEnterpriseAssoc) -> Self {
+        Self {
+            enterprise: association.enterprise.into(),
+            gateway_type: association.gateway_type.into(),
+            edge_id: association.edge_id,
+            edge_name: association.edge_name,
+            edge_logical_id: association.edge_logical_id,
+            pinned: association.pinned != 0,
+        }
+    }
+}
+
+/// An edge connected to a gateway.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectedEdge {
+    /// The edge's VCE ID, a UUID.
+    pub vceid: String,
+}
+
+impl From<api_v1::gateway::ConnectedEdge> for ConnectedEdge {
+    fn from(edge: api_v1::gateway::ConnectedEdge) -> Self {
+        Self { vceid: edge.vceid }
+    }
+}
+
+impl From<NetworkGetNetworkGatewaysResultItem> for Gateway {
+    fn from(gateway: NetworkGetNetworkGatewaysResultItem) -> Self {
+        Self {
+            id: gateway.id,
+            logical_id: gateway.logical_id,
+            name: gateway.name,
+            description: gateway.description,
+            dns_name: gateway.dns_name,
+            network_id: gateway.network_id,
+            enterprise_proxy_id: gateway.enterprise_proxy_id,
+            site_id: gateway.site_id,
+            software_version: gateway.software_version,
+            build_number: gateway.build_number,
+            device_id: gateway.device_id,
+            ip_address: address(gateway.ip_address),
+            ip_v6_address: address(gateway.ip_v6_address),
+            private_ip_address: address(gateway.private_ip_address),
+            gateway_state: gateway.gateway_state.into(),
+            activation_state: gateway.activation_state.into(),
+            service_state: gateway.service_state.into(),
+            utilization: gateway.utilization,
+            connected_edges: gateway.connected_edges,
+            connected_edge_list: gateway
+                .connected_edge_list
+                .map(|edges| edges.into_iter().map(ConnectedEdge::from).collect()),
+            is_load_balanced: gateway.is_load_balanced.0,
+            alerts_enabled: gateway.alerts_enabled.map(|enabled| enabled.0),
+            created: timestamp(gateway.created),
+            modified: timestamp(gateway.modified),
+            activation_time: timestamp(gateway.activation_time),
+            last_contact: timestamp(gateway.last_contact),
+            service_up_since: timestamp(gateway.service_up_since),
+            system_up_since: timestamp(gateway.system_up_since),
+            site: gateway.site.map(Site::from),
+            roles: gateway
+                .roles
+                .map(|roles| roles.into_iter().map(GatewayRole::from).collect()),
+            enterprises: gateway
+                .enterprises
+                .map(|enterprises| enterprises.into_iter().map(Enterprise::from).collect()),
+            enterprise_associations: gateway.enterprise_associations.map(|associations| {
+                associations
+                    .into_iter()
+                    .map(EnterpriseAssociation::from)
+                    .collect()
+            }),
+            hand_off_edges: gateway.hand_off_edges.map(|edges| {
+                edges
+                    .into_iter()
+                    .map(|edge| Edge::from(edge._edge_object))
+                    .collect()
+            }),
+            data_centers: gateway
+                .data_centers
+                .map(|data_centers| data_centers.into_iter().map(DataCenter::from).collect()),
+            enterprise_association_count: gateway
+                .enterprise_association_count
+                .map(EnterpriseAssociationCount::from),
+            pools: gateway.pools.map(|pools| {
+                pools
+                    .into_iter()
+                    .map(|pool| GatewayPool::from(pool.gateway_pool))
+                    .collect()
+            }),
+            certificates: gateway
+                .certificates
+                .map(|certificates| certificates.into_iter().map(Certificate::from).collect()),
+        }
+    }
+}
+
+impl From<api_v2::gateway::Gateway> for Gateway {
+    fn from(gateway: api_v2::gateway::Gateway) -> Self {
+        Self {
+            id: gateway.id,
+            logical_id: gateway.logical_id,
+            name: gateway.name,
+            description: gateway.description,
+            dns_name: gateway.dns_name,
+            network_id: gateway.network_id,
+            enterprise_proxy_id: gateway.enterprise_proxy_id,
+            site_id: gateway.site_id,
+            software_version: gateway.software_version,
+            build_number: gateway.build_number,
+            device_id: gateway.device_id,
+            ip_address: gateway.ip_address,
+            ip_v6_address: gateway.ip_v6_address,
+            private_ip_address: gateway.private_ip_address,
+            gateway_state: GatewayState::from_vco_name(&gateway.gateway_state),
+            activation_state: ActivationState::from_vco_name(&gateway.activation_state),
+            service_state: ServiceState::from_vco_name(&gateway.service_state),
+            utilization: gateway.utilization,
+            connected_edges: gateway.connected_edges,
+            connected_edge_list: None,
+            is_load_balanced: gateway.is_load_balanced,
+            alerts_enabled: gateway.alerts_enabled,
+            created: gateway.created,
+            modified: gateway.modified,
+            activation_time: gateway.activation_time,
+            last_contact: gateway.last_contact,
+            service_up_since: gateway.service_up_since,
+            system_up_since: gateway.system_up_since,
+            site: None,
+            roles: None,
+            enterprises: None,
+            enterprise_associations: None,
+            hand_off_edges: None,
+            data_centers: None,
+            enterprise_association_count: None,
+            pools: None,
+            certificates: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_gateway {
+    use super::*;
+
+    /// Convert the gateways in one of `api_v1`'s fixtures.
+    fn convert(src: &str) -> Vec<Gateway> {
+        let items: Vec<NetworkGetNetworkGatewaysResultItem> = serde_json::from_str(src).unwrap();
+        items.into_iter().map(Gateway::from).collect()
+    }
+
+    #[test]
+    fn test_from_api_v1() {
+        let gateways = convert(include_str!(
+            "../../../api_v1/tests/fixtures/5.2/network_getNetworkGateways.json"
+        ));
+        assert_eq!(gateways[0].gateway_state, GatewayState::Connected);
+        assert!(gateways[0].activation_time.is_some());
+        assert!(gateways[0].private_ip_address.is_some());
+        assert_eq!(gateways[0].enterprises, None);
+        assert_eq!(gateways[0].connected_edge_list.as_ref().unwrap().len(), 1);
+        assert_eq!(gateways[1].connected_edge_list, None);
+
+        // A gateway that was never activated.
+        assert_eq!(gateways[1].activation_time, None);
+        assert_eq!(gateways[1].private_ip_address, None);
+
+        let gateways = convert(include_str!(
+            "../../../api_v1/tests/fixtures/5.2/network_getNetworkGateways.with_all.json"
+        ));
+        assert_eq!(gateways[0].enterprises.as_ref().unwrap().len(), 1);
+        let site = gateways[0].site.as_ref().unwrap();
+        assert_eq!(site.name.as_deref(), Some("Frankfurt DC"));
+        assert_eq!(site.address.city.as_deref(), Some("Frankfurt"));
+        assert_eq!(site.shipping_address, None);
+        assert_eq!(
+            gateways[0].roles.as_ref().unwrap()[0],
+            GatewayRole {
+                role: GatewayRoleType::DataPlane,
+                required: true,
+            }
+        );
+        let association = &gateways[0].enterprise_associations.as_ref().unwrap()[0];
+        assert_eq!(association.enterprise.name, "Acme Corp 12");
+        assert_eq!(association.gateway_type, GatewayType::Primary);
+        assert_eq!(association.edge_name.as_deref(), Some("branch-301"));
+        assert!(!association.pinned);
+        assert_eq!(gateways[0].hand_off_edges.as_ref().unwrap().len(), 1);
+        assert_eq!(gateways[0].data_centers.as_ref().unwrap()[0].name, "FRA1");
+        assert_eq!(gateways[0].pools.as_ref().unwrap()[0].name, "Default Pool");
+        // The fixture's certificate is anonymized, so there's nothing to parse.
+        let certificate = &gateways[0].certificates.as_ref().unwrap()[0];
+        assert_eq!(certificate.details, None);
+        assert!(certificate.valid_to.is_some());
+        assert_eq!(
+            gateways[0].enterprise_association_count,
+            Some(EnterpriseAssociationCount {
+                enterprises: 1,
+                edges: 1,
+                profiles: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_api_v1() {
+        use super::super::{
+            HandoffSubnet, IcmpProbe, IcmpResponder, PostalAddress, SubnetHandoffType,
+            SyslogCollector, SyslogFacility, SyslogProtocol,
+        };
+
+        let attributes = GatewayAttributes {
+            site: Some(SiteAttributes {
+                address: PostalAddress {
+                    city: Some("Singapore".into()),
+                    ..Default::default()
+                },
+                shipping_same_as_location: Some(true),
+                ..Default::default()
+            }),
+            hand_off_detail: Some(HandoffDetail {
+                kind: Some("VLAN".into()),
+                subnets: vec![HandoffSubnet {
+                    name: "partner".into(),
+                    route_cost: 0,
+                    cidr_ip: Ipv4Addr::new(198, 51, 100, 0),
+                    cidr_prefix: 24,
+                    encrypt: false,
+                    hand_off_type: SubnetHandoffType::Nat,
+                }],
+                icmp_probe: IcmpProbe::default(),
+                icmp_responder: IcmpResponder {
+                    enabled: true,
+                    ip_address: Ipv4Addr::new(198, 51, 100, 1),
+                    mode: "CONDITIONAL".into(),
+                },
+            }),
+            syslog: Some(SyslogSettings {
+                tag: "vcg".into(),
+                facility: SyslogFacility::Local3,
+                collectors: vec![SyslogCollector {
+                    host: Some("syslog.example.com".into()),
+                    protocol: Some(SyslogProtocol::Udp),
+                    ..Default::default()
+                }],
+            }),
+            service_state: Some(ServiceState::Quiesced),
+            ..Default::default()
+        };
+        let attributes = api_v1::gateway::GatewayAttributes::from(attributes);
+        assert_eq!(
+            serde_json::to_value(&attributes).unwrap(),
+            serde_json::json!({
+                "site": {"city": "Singapore", "shippingSameAsLocation": 1},
+                "handOffDetail": {
+                    "type": "VLAN",
+                    "subnets": [{
+                        "name": "partner",
+                        "routeCost": 0,
+                        "cidrIp": "198.51.100.0",
+                        "cidrPrefix": 24,
+                        "encrypt": false,
+                        "handOffType": "NAT",
+                    }],
+                    "icmpProbe": {"enabled": false},
+                    "icmpResponder": {
+                        "enabled": true,
+                        "ipAddress": "198.51.100.1",
+                        "mode": "CONDITIONAL",
+                    },
+                },
+                "syslog": {
+                    "tag": "vcg",
+                    "facilityCode": "LOCAL3",
+                    "collectors": [{
+                        "host": "syslog.example.com",
+                        "port": null,
+                        "protocol": "UDP",
+                        "severity": null,
+                    }],
+                },
+                "serviceState": "QUIESCED",
+            })
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Gateway pools, the sets of gateways enterprises are assigned to.
+
+use time::OffsetDateTime;
+
+use api_v1::gateway::{GatewayPoolGateway, NetworkGetNetworkGatewayPoolsResultItem};
+
+use super::{timestamp, vco_enum, LogicalId};
+use crate::id::{EnterpriseProxyId, GatewayId, GatewayPoolId, NetworkId};
+
+vco_enum!(
+    /// Whether a pool's gateways hand off traffic to partner networks.
+    HandoffType from api_v1::gateway::GatewayHandoffType {
+        None = "NONE",
+        Allow = "ALLOW",
+        Only = "ONLY",
+    }
+);
+
+/// A gateway pool to add to a network. It starts with no gateways.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GatewayPoolProvision {
+    pub network_id: NetworkId,
+    pub name: String,
+    pub description: Option<String>,
+    pub hand_off_type: Option<HandoffType>,
+}
+
+impl From<GatewayPoolProvision> for api_v1::gateway::InsertNetworkGatewayPool {
+    fn from(pool: GatewayPoolProvision) -> Self {
+        Self {
+            network_id: pool.network_id,
+            name: pool.name,
+            description: pool.description,
+            hand_off_type: pool.hand_off_type.map(Into::into),
+        }
+    }
+}
+
+/// Changes to a gateway pool's attributes. Those that are `None` are left as they are.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GatewayPoolAttributes {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub hand_off_type: Option<HandoffType>,
+}
+
+impl From<GatewayPoolAttributes> for api_v1::gateway::GatewayPoolAttributes {
+    fn from(attributes: GatewayPoolAttributes) -> Self {
+        Self {
+            name: attributes.name,
+            description: attributes.description,
+            hand_off_type: attributes.hand_off_type.map(Into::into),
+        }
+    }
+}
+
+/// A gateway pool.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GatewayPool {
+    pub id: GatewayPoolId,
+    pub logical_id: LogicalId,
+    pub name: String,
+    pub description: Option<String>,
+    pub network_id: NetworkId,
+    /// The partner the pool belongs to, if it isn't the operator's.
+    pub enterprise_proxy_id: Option<EnterpriseProxyId>,
+    /// Whether new enterprises are assigned to this pool.
+    pub is_default: bool,
+    pub ip_v4_enabled: bool,
+    pub ip_v6_enabled: bool,
+    pub hand_off_type: HandoffType,
+    pub created: Option<OffsetDateTime>,
+    pub modified: Option<OffsetDateTime>,
+    /// The gateways in the pool, if they were asked for.
+    pub gateways: Option<Vec<PoolGateway>>,
+}
+
+impl From<api_v1::gateway::GatewayPool> for GatewayPool {
+    fn from(pool: api_v1::gateway::GatewayPool) -> Self {
+        Self {
+            id: pool.id,
+            logical_id: pool.logical_id,
+            name: pool.name,
+            description: pool.description,
+            network_id: pool.network_id,
+            enterprise_proxy_id: pool.enterprise_proxy_id,
+            is_default: pool.is_default.0,
+            ip_v4_enabled: pool.ip_v4_enabled.0,
+            ip_v6_enabled: pool.ip_v6_enabled.0,
+            hand_off_type: pool.hand_off_type.into(),
+            created: timestamp(pool.created),
+            modified: timestamp(pool.modified),
+            gateways: None,
+        }
+    }
+}
+
+impl From<NetworkGetNetworkGatewayPoolsResultItem> for GatewayPool {
+    fn from(item: NetworkGetNetworkGatewayPoolsResultItem) -> Self {
+        Self {
+            gateways: item
+                .gateways
+                .map(|gateways| gateways.into_iter().map(PoolGateway::from).collect()),
+            ..item.gateway_pool.into()
+        }
+    }
+}
+
+/// A gateway in a pool.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolGateway {
+    pub id: GatewayId,
+    pub logical_id: LogicalId,
+    pub name: String,
+    /// The ID of the gateway's membership of the pool, which VCO removes it from the pool by.
+    pub gateway_pool_assoc_id: Option<i32>,
+}
+
+impl From<GatewayPoolGateway> for PoolGateway {
+    fn from(gateway: GatewayPoolGateway) -> Self {
+        Self {
+            id: gateway.id,
+            logical_id: gateway.logical_id,
+            name: gateway.name,
+            gateway_pool_assoc_id: gateway.gateway_pool_assoc_id,
+        }
+    }
+}
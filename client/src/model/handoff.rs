@@ -0,0 +1,109 @@
+//! How a gateway hands off traffic to partner networks.
+
+use std::net::Ipv4Addr;
+
+use api_v1::network_address::Address;
+
+use super::vco_enum;
+
+vco_enum!(
+    /// How a handoff subnet's traffic is handed off.
+    SubnetHandoffType from api_v1::gateway::GatewayHandoffDetailSubnetHandoffType {
+        Nat = "NAT",
+        Vlan = "VLAN",
+    }
+);
+
+/// How a gateway hands off traffic to partner networks, when adding or changing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandoffDetail {
+    /// Like "VLAN".
+    pub kind: Option<String>,
+    pub subnets: Vec<HandoffSubnet>,
+    pub icmp_probe: IcmpProbe,
+    pub icmp_responder: IcmpResponder,
+}
+
+/// A subnet a gateway hands off traffic for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandoffSubnet {
+    pub name: String,
+    pub route_cost: u8,
+    pub cidr_ip: Ipv4Addr,
+    pub cidr_prefix: u8,
+    pub encrypt: bool,
+    pub hand_off_type: SubnetHandoffType,
+}
+
+/// How a gateway checks that the partner network is reachable.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IcmpProbe {
+    pub enabled: bool,
+    pub probe_type: Option<String>,
+    pub c_tag: Option<i32>,
+    pub s_tag: Option<i32>,
+    pub destination_ip: Option<Ipv4Addr>,
+    pub frequency_seconds: Option<i32>,
+    pub threshold: Option<i32>,
+}
+
+/// How a gateway answers the partner network's probes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcmpResponder {
+    pub enabled: bool,
+    pub ip_address: Ipv4Addr,
+    /// Like "CONDITIONAL".
+    pub mode: String,
+}
+
+impl From<HandoffDetail> for api_v1::gateway::GatewayHandoffDetail {
+    fn from(detail: HandoffDetail) -> Self {
+        Self {
+            typ: detail.kind,
+            subnets: detail.subnets.into_iter().map(Into::into).collect(),
+            icmp_probe: detail.icmp_probe.into(),
+            icmp_responder: detail.icmp_responder.into(),
+            unhandled_fields: Default::default(),
+        }
+    }
+}
+
+impl From<HandoffSubnet> for api_v1::gateway::GatewayHandoffDetailSubnet {
+    fn from(subnet: HandoffSubnet) -> Self {
+        Self {
+            name: subnet.name,
+            route_cost: subnet.route_cost,
+            cidr_ip: Address::Some(subnet.cidr_ip),
+            cidr_prefix: subnet.cidr_prefix,
+            encrypt: subnet.encrypt,
+            hand_off_type: subnet.hand_off_type.into(),
+            unhandled_fields: Default::default(),
+        }
+    }
+}
+
+impl From<IcmpProbe> for api_v1::gateway::GatewayHandoffDetailIcmpProbe {
+    fn from(probe: IcmpProbe) -> Self {
+        Self {
+            enabled: probe.enabled,
+            probe_type: probe.probe_type,
+            c_tag: probe.c_tag,
+            s_tag: probe.s_tag,
+            destination_ip: probe.destination_ip.map(Address::Some),
+            frequency_seconds: probe.frequency_seconds,
+            threshold: probe.threshold,
+            unhandled_fields: Default::default(),
+        }
+    }
+}
+
+impl From<IcmpResponder> for api_v1::gateway::GatewayHandoffDetailIcmpResponder {
+    fn from(responder: IcmpResponder) -> Self {
+        Self {
+            enabled: responder.enabled,
+            ip_address: Address::Some(responder.ip_address),
+            mode: responder.mode,
+            unhandled_fields: Default::default(),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! The metrics VCO keeps about gateways.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use time::{Duration, OffsetDateTime};
+
+use api_v1::gateway::{
+    GatewayMetric, GatewayStatusMetric, GatewayStatusMetricSeries, GetGatewayStatusMetricsResult,
+};
+
+use super::timestamp;
+
+/// A metric VCO keeps for each gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Metric {
+    TunnelCount,
+    TunnelCountV6,
+    MemoryPct,
+    CpuPct,
+    FlowCount,
+    HandoffQueueDrops,
+    ConnectedEdges,
+}
+
+impl Metric {
+    /// Every metric VCO keeps for gateways.
+    pub const ALL: &'static [Metric] = &[
+        Metric::TunnelCount,
+        Metric::TunnelCountV6,
+        Metric::MemoryPct,
+        Metric::CpuPct,
+        Metric::FlowCount,
+        Metric::HandoffQueueDrops,
+        Metric::ConnectedEdges,
+    ];
+
+    /// VCO's name for the metric, e.g. "cpuPct".
+    pub fn vco_name(&self) -> &'static str {
+        match self {
+            Metric::TunnelCount => "tunnelCount",
+            Metric::TunnelCountV6 => "tunnelCountV6",
+            Metric::MemoryPct => "memoryPct",
+            Metric::CpuPct => "cpuPct",
+            Metric::FlowCount => "flowCount",
+            Metric::HandoffQueueDrops => "handoffQueueDrops",
+            Metric::ConnectedEdges => "connectedEdges",
+        }
+    }
+}
+
+impl Display for Metric {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.vco_name())
+    }
+}
+
+impl From<Metric> for GatewayMetric {
+    fn from(metric: Metric) -> Self {
+        match metric {
+            Metric::TunnelCount => GatewayMetric::TunnelCount,
+            Metric::TunnelCountV6 => GatewayMetric::TunnelCountV6,
+            Metric::MemoryPct => GatewayMetric::MemoryPct,
+            Metric::CpuPct => GatewayMetric::CpuPct,
+            Metric::FlowCount => GatewayMetric::FlowCount,
+            Metric::HandoffQueueDrops => GatewayMetric::HandoffQueueDrops,
+            Metric::ConnectedEdges => GatewayMetric::ConnectedEdges,
+        }
+    }
+}
+
+/// A gateway's status metrics over an interval.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GatewayMetricSeries {
+    /// The values of each metric VCO gave.
+    pub metrics: BTreeMap<Metric, MetricSeries>,
+}
+
+impl GatewayMetricSeries {
+    /// The values of `metric`, if VCO gave them.
+    pub fn get(&self, metric: Metric) -> Option<&MetricSeries> {
+        self.metrics.get(&metric)
+    }
+}
+
+impl From<GetGatewayStatusMetricsResult> for GatewayMetricSeries {
+    fn from(result: GetGatewayStatusMetricsResult) -> Self {
+        let metrics = [
+            (Metric::TunnelCount, result.tunnel_count),
+            (Metric::TunnelCountV6, result.tunnel_count_v6),
+            (Metric::MemoryPct, result.memory_pct),
+            (Metric::CpuPct, result.cpu_pct),
+            (Metric::FlowCount, result.flow_count),
+            (Metric::HandoffQueueDrops, result.handoff_queue_drops),
+            (Metric::ConnectedEdges, result.connected_edges),
+        ];
+        Self {
+            metrics: metrics
+                .into_iter()
+                .filter_map(|(metric, values)| Some((metric, values?.into())))
+                .collect(),
+        }
+    }
+}
+
+/// One metric's values over an interval.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MetricSeries {
+    /// The values, oldest first. Times VCO has no value for are left out.
+    pub points: Vec<MetricPoint>,
+    pub summary: MetricSummary,
+}
+
+impl From<GatewayStatusMetric> for MetricSeries {
+    fn from(metric: GatewayStatusMetric) -> Self {
+        let points: Vec<MetricPoint> = metric.series.into_iter().flat_map(points).collect();
+        // VCO's summary covers the whole interval, so it's preferred to working one out.
+        let summary = MetricSummary::of(&points);
+        Self {
+            summary: MetricSummary {
+                min: metric.min.or(summary.min),
+                max: metric.max.or(summary.max),
+                average: metric.average.or(summary.average),
+            },
+            points,
+        }
+    }
+}
+
+/// The points in a run of values, one every tick from its start. A point whose time is out of
+/// range is left out.
+fn points(series: GatewayStatusMetricSeries) -> Vec<MetricPoint> {
+    let Some(start) = timestamp(series.start_time) else {
+        return vec![];
+    };
+    let tick = Duration::milliseconds(series.tick_interval.into());
+    series
+        .data
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, value)| {
+            let offset = tick.checked_mul(i32::try_from(i).ok()?)?;
+            Some(MetricPoint {
+                time: start.checked_add(offset)?,
+                value: value?,
+            })
+        })
+        .collect()
+}
+
+/// A metric's value at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricPoint {
+    pub time: OffsetDateTime,
+    pub value: f64,
+}
+
+/// The least, greatest and average of a metric's values, or `None` if there are none.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MetricSummary {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub average: Option<f64>,
+}
+
+impl MetricSummary {
+    /// Summarize `points`.
+    pub fn of(points: &[MetricPoint]) -> Self {
+        let values = || points.iter().map(|point| point.value);
+        Self {
+            min: values().reduce(f64::min),
+            max: values().reduce(f64::max),
+            average: (!points.is_empty()).then(|| values().sum::<f64>() / points.len() as f64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_metric {
+    use super::*;
+    use api_v1::date_time::DateTime;
+
+    #[test]
+    fn test_vco_name() {
+        for &metric in Metric::ALL {
+            let wire = serde_json::to_value(GatewayMetric::from(metric)).unwrap();
+            assert_eq!(wire, metric.vco_name());
+        }
+    }
+
+    #[test]
+    fn test_from_api_v1() {
+        let result: GetGatewayStatusMetricsResult = serde_json::from_str(include_str!(
+            "../../../api_v1/tests/fixtures/5.2/metrics_getGatewayStatusMetrics.json"
+        ))
+        .unwrap();
+        let series = GatewayMetricSeries::from(result);
+        assert_eq!(series.metrics.len(), Metric::ALL.len());
+
+        let cpu_pct = series.get(Metric::CpuPct).unwrap();
+        assert_eq!(cpu_pct.points.len(), 4);
+        assert_eq!(cpu_pct.points[2].value, 12.25);
+        assert_eq!(
+            cpu_pct.points[1].time - cpu_pct.points[0].time,
+            Duration::minutes(5)
+        );
+        assert_eq!(
+            cpu_pct.summary,
+            MetricSummary {
+                min: Some(4.5),
+                max: Some(12.25),
+                average: Some(7.5),
+            }
+        );
+
+        // The tick with no value is left out, but the times of those after it aren't moved.
+        let flow_count = series.get(Metric::FlowCount).unwrap();
+        assert_eq!(flow_count.points.len(), 3);
+        assert_eq!(
+            flow_count.points[2].time - flow_count.points[0].time,
+            Duration::minutes(15)
+        );
+    }
+
+    #[test]
+    fn test_points_out_of_range() {
+        let series = GatewayStatusMetricSeries {
+            start_time: DateTime::from_rfc3339("9999-12-31T22:00:00Z").unwrap(),
+            tick_interval: 60 * 60 * 1000,
+            data: vec![Some(1.0), Some(2.0), Some(3.0)],
+            unhandled_fields: Default::default(),
+        };
+        let points = points(series);
+        assert_eq!(
+            points.iter().map(|point| point.value).collect::<Vec<_>>(),
+            [1.0, 2.0]
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        let time = OffsetDateTime::UNIX_EPOCH;
+        let points = [1.0, 4.0, 2.5].map(|value| MetricPoint { time, value });
+        assert_eq!(
+            MetricSummary::of(&points),
+            MetricSummary {
+                min: Some(1.0),
+                max: Some(4.0),
+                average: Some(2.5),
+            }
+        );
+        assert_eq!(MetricSummary::of(&[]), MetricSummary::default());
+    }
+}
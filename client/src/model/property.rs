@@ -0,0 +1,130 @@
+//! VCO's system properties.
+
+use std::fmt::{Display, Formatter};
+
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use api_v1::property::{GetSystemPropertiesResultItem, PropertyDataType};
+
+use super::timestamp;
+
+/// The value of a system property, typed by the property's data type.
+///
+/// VCO sends every value as a string. One that doesn't parse as its data type, or whose data type
+/// this crate doesn't know, is kept as a `String`.
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum PropertyValue {
+    String(String),
+    Number(f64),
+    Boolean(bool),
+    /// JSON text, as VCO sent it.
+    Json(String),
+    Date(OffsetDateTime),
+    Datetime(OffsetDateTime),
+}
+
+impl PropertyValue {
+    /// Type the string `value` VCO sent according to `data_type`.
+    fn parse(value: String, data_type: &PropertyDataType) -> Self {
+        let parsed = match data_type {
+            PropertyDataType::Number => value.parse().ok().map(PropertyValue::Number),
+            PropertyDataType::Boolean => match value.as_str() {
+                "true" | "1" => Some(PropertyValue::Boolean(true)),
+                "false" | "0" => Some(PropertyValue::Boolean(false)),
+                _ => None,
+            },
+            PropertyDataType::Json => Some(PropertyValue::Json(value.clone())),
+            PropertyDataType::Date => OffsetDateTime::parse(&value, &Rfc3339)
+                .ok()
+                .map(PropertyValue::Date),
+            PropertyDataType::Datetime => OffsetDateTime::parse(&value, &Rfc3339)
+                .ok()
+                .map(PropertyValue::Datetime),
+            _ => None,
+        };
+        parsed.unwrap_or(PropertyValue::String(value))
+    }
+}
+
+/// Values are written the way VCO would send them.
+impl Display for PropertyValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PropertyValue::String(value) | PropertyValue::Json(value) => f.write_str(value),
+            PropertyValue::Number(value) => write!(f, "{value}"),
+            PropertyValue::Boolean(value) => write!(f, "{value}"),
+            PropertyValue::Date(value) | PropertyValue::Datetime(value) => {
+                f.write_str(&value.format(&Rfc3339).map_err(|_| std::fmt::Error)?)
+            }
+        }
+    }
+}
+
+/// A system property, i.e. a VCO-wide setting.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SystemProperty {
+    pub name: String,
+    pub value: PropertyValue,
+    pub default_value: Option<PropertyValue>,
+    pub is_read_only: bool,
+    pub is_password: bool,
+    pub description: Option<String>,
+    pub created: Option<OffsetDateTime>,
+    pub modified: Option<OffsetDateTime>,
+}
+
+impl From<GetSystemPropertiesResultItem> for SystemProperty {
+    fn from(item: GetSystemPropertiesResultItem) -> Self {
+        let property = item.property;
+        let data_type = property.data_type;
+        Self {
+            name: property.name,
+            value: PropertyValue::parse(property.value, &data_type),
+            default_value: property
+                .default_value
+                .map(|value| PropertyValue::parse(value, &data_type)),
+            is_read_only: property.is_read_only.0,
+            is_password: property.is_password.0,
+            description: property.description,
+            created: timestamp(item.created),
+            modified: timestamp(item.modified),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_property {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let parse = |value: &str, data_type| PropertyValue::parse(value.into(), &data_type);
+        assert_eq!(
+            parse("1.5", PropertyDataType::Number),
+            PropertyValue::Number(1.5)
+        );
+        assert_eq!(
+            parse("1", PropertyDataType::Boolean),
+            PropertyValue::Boolean(true)
+        );
+        assert_eq!(
+            parse("{\"a\": 1}", PropertyDataType::Json).to_string(),
+            "{\"a\": 1}"
+        );
+        let value = parse("2023-06-18T12:00:00Z", PropertyDataType::Datetime);
+        assert!(matches!(value, PropertyValue::Datetime(_)));
+        assert_eq!(value.to_string(), "2023-06-18T12:00:00Z");
+
+        // Values that don't match their type, or whose type isn't known, are kept as they are.
+        assert_eq!(
+            parse("lots", PropertyDataType::Number),
+            PropertyValue::String("lots".into())
+        );
+        assert_eq!(
+            parse("6.0.0", PropertyDataType::Other("SEMVER".into())),
+            PropertyValue::String("6.0.0".into())
+        );
+    }
+}
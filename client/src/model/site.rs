@@ -0,0 +1,134 @@
+//! Sites: where gateways and edges are, and who to call about them.
+
+use time::OffsetDateTime;
+
+use api_v1::tinyint::TinyInt;
+
+use super::{timestamp, LogicalId};
+use crate::id::SiteId;
+
+/// A postal address. VCO leaves any part of it out that it wasn't told, and when setting one the
+/// parts that are `None` aren't sent.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PostalAddress {
+    pub street_address: Option<String>,
+    pub street_address2: Option<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub postal_code: Option<String>,
+    pub country: Option<String>,
+}
+
+/// Where a gateway or edge is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Site {
+    pub id: SiteId,
+    pub logical_id: LogicalId,
+    pub name: Option<String>,
+    pub contact_name: String,
+    pub contact_phone: Option<String>,
+    pub contact_mobile: Option<String>,
+    pub contact_email: Option<String>,
+    pub address: PostalAddress,
+    pub lat: f64,
+    pub lon: f64,
+    /// Like "Europe/Berlin".
+    pub timezone: String,
+    /// Like "de-DE".
+    pub locale: String,
+    pub shipping_contact_name: Option<String>,
+    /// Where to ship things to, or `None` if it's `address`.
+    pub shipping_address: Option<PostalAddress>,
+    pub created: Option<OffsetDateTime>,
+    pub modified: Option<OffsetDateTime>,
+}
+
+/// The parts of a site that can be set when adding or changing a gateway. Those that are `None`
+/// are left to VCO, or as they are.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SiteAttributes {
+    pub name: Option<String>,
+    pub contact_name: Option<String>,
+    pub contact_phone: Option<String>,
+    pub contact_mobile: Option<String>,
+    pub contact_email: Option<String>,
+    pub address: PostalAddress,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    /// Like "Europe/Berlin".
+    pub timezone: Option<String>,
+    /// Like "de-DE".
+    pub locale: Option<String>,
+    /// Whether things are shipped to `address` rather than `shipping_address`.
+    pub shipping_same_as_location: Option<bool>,
+    pub shipping_contact_name: Option<String>,
+    pub shipping_address: PostalAddress,
+}
+
+impl From<api_v1::gateway::Site> for Site {
+    fn from(site: api_v1::gateway::Site) -> Self {
+        let shipping_address = (!site.shipping_same_as_location.0).then_some(PostalAddress {
+            street_address: site.shipping_address,
+            street_address2: site.shipping_address2,
+            city: site.shipping_city,
+            state: site.shipping_state,
+            postal_code: site.shipping_postal_code,
+            country: site.shipping_country,
+        });
+        Self {
+            id: site.id,
+            logical_id: site.logical_id,
+            name: site.name,
+            contact_name: site.contact_name,
+            contact_phone: site.contact_phone,
+            contact_mobile: site.contact_mobile,
+            contact_email: site.contact_email,
+            address: PostalAddress {
+                street_address: site.street_address,
+                street_address2: site.street_address2,
+                city: site.city,
+                state: site.state,
+                postal_code: site.postal_code,
+                country: site.country,
+            },
+            lat: site.lat,
+            lon: site.lon,
+            timezone: site.timezone,
+            locale: site.locale,
+            shipping_contact_name: site.shipping_contact_name,
+            shipping_address,
+            created: timestamp(site.created),
+            modified: timestamp(site.modified),
+        }
+    }
+}
+
+impl From<SiteAttributes> for api_v1::gateway::SiteAttributes {
+    fn from(site: SiteAttributes) -> Self {
+        Self {
+            name: site.name,
+            contact_name: site.contact_name,
+            contact_phone: site.contact_phone,
+            contact_mobile: site.contact_mobile,
+            contact_email: site.contact_email,
+            street_address: site.address.street_address,
+            street_address2: site.address.street_address2,
+            city: site.address.city,
+            state: site.address.state,
+            postal_code: site.address.postal_code,
+            country: site.address.country,
+            lat: site.lat,
+            lon: site.lon,
+            timezone: site.timezone,
+            locale: site.locale,
+            shipping_same_as_location: site.shipping_same_as_location.map(TinyInt),
+            shipping_contact_name: site.shipping_contact_name,
+            shipping_address: site.shipping_address.street_address,
+            shipping_address2: site.shipping_address.street_address2,
+            shipping_city: site.shipping_address.city,
+            shipping_state: site.shipping_address.state,
+            shipping_country: site.shipping_address.country,
+            shipping_postal_code: site.shipping_address.postal_code,
+        }
+    }
+}
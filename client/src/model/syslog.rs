@@ -0,0 +1,72 @@
+//! Where a gateway sends its logs.
+
+use super::vco_enum;
+
+vco_enum!(
+    /// The syslog facility a gateway logs as.
+    SyslogFacility from api_v1::gateway::SyslogLocalFacility {
+        Local0 = "LOCAL0",
+        Local1 = "LOCAL1",
+        Local2 = "LOCAL2",
+        Local3 = "LOCAL3",
+        Local4 = "LOCAL4",
+        Local5 = "LOCAL5",
+        Local6 = "LOCAL6",
+        Local7 = "LOCAL7",
+    }
+);
+
+vco_enum!(
+    /// The least severe messages a syslog collector is sent.
+    SyslogSeverity from api_v1::gateway::GatewaySyslogCollectorSeverity {
+        Info = "INFO",
+    }
+);
+
+vco_enum!(
+    /// How logs are sent to a syslog collector.
+    SyslogProtocol from api_v1::common::TcpOrUdp {
+        Tcp = "TCP",
+        Udp = "UDP",
+    }
+);
+
+/// Where a gateway sends its logs, when changing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyslogSettings {
+    pub tag: String,
+    pub facility: SyslogFacility,
+    pub collectors: Vec<SyslogCollector>,
+}
+
+/// A syslog server a gateway sends its logs to.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SyslogCollector {
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub protocol: Option<SyslogProtocol>,
+    pub severity: Option<SyslogSeverity>,
+}
+
+impl From<SyslogSettings> for api_v1::gateway::GatewaySyslogSettings {
+    fn from(settings: SyslogSettings) -> Self {
+        Self {
+            tag: settings.tag,
+            facility_code: settings.facility.into(),
+            collectors: settings.collectors.into_iter().map(Into::into).collect(),
+            unhandled_fields: Default::default(),
+        }
+    }
+}
+
+impl From<SyslogCollector> for api_v1::gateway::GatewaySyslogCollectorSettings {
+    fn from(collector: SyslogCollector) -> Self {
+        Self {
+            host: collector.host,
+            port: collector.port,
+            protocol: collector.protocol.map(Into::into),
+            severity: collector.severity.map(Into::into),
+            unhandled_fields: Default::default(),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Certificate pinning: trusting VCO's certificate by its fingerprint rather than by who signed it.
+
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use rustls::client::{ServerCertVerified, ServerCertVerifier};
+use rustls::{Certificate, ClientConfig, ServerName};
+use sha2::{Digest, Sha256};
+
+use crate::error::ClientError;
+
+/// Accepts exactly one certificate, by the SHA-256 fingerprint of its DER encoding.
+#[derive(Debug)]
+struct PinnedCertificate {
+    sha256: [u8; 32],
+}
+
+impl ServerCertVerifier for PinnedCertificate {
+    /// The pin stands in for the CA, name and expiry checks: only the one certificate matches it.
+    /// The handshake signatures are still checked against the certificate's key.
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let sha256: [u8; 32] = Sha256::digest(&end_entity.0).into();
+        if sha256 == self.sha256 {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(format!(
+                "certificate {} doesn't match the pinned fingerprint",
+                to_fingerprint(&sha256)
+            )))
+        }
+    }
+}
+
+/// A TLS config that accepts only the certificate whose SHA-256 fingerprint is `fingerprint`, in
+/// hex with or without colons.
+pub(crate) fn pinned_tls_config(fingerprint: &str) -> Result<ClientConfig, ClientError> {
+    let verifier = PinnedCertificate {
+        sha256: parse_fingerprint(fingerprint)?,
+    };
+    Ok(ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(Arc::new(verifier))
+        .with_no_client_auth())
+}
+
+fn parse_fingerprint(fingerprint: &str) -> Result<[u8; 32], ClientError> {
+    let bad = || ClientError::BadCertificate(format!("Bad SHA-256 fingerprint {fingerprint:?}"));
+    let hex: Vec<char> = fingerprint.chars().filter(|c| *c != ':').collect();
+    // `from_str_radix` would take a sign, e.g. "+F", so check the digits first.
+    if hex.len() != 64 || !hex.iter().all(char::is_ascii_hexdigit) {
+        return Err(bad());
+    }
+    let mut sha256 = [0; 32];
+    for (byte, pair) in sha256.iter_mut().zip(hex.chunks(2)) {
+        let pair: String = pair.iter().collect();
+        *byte = u8::from_str_radix(&pair, 16).map_err(|_| bad())?;
+    }
+    Ok(sha256)
+}
+
+fn to_fingerprint(sha256: &[u8]) -> String {
+    sha256
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod test_pin {
+    use super::*;
+
+    const FINGERPRINT: &str =
+        "FC:68:4A:A7:9B:C7:26:7F:AB:C1:54:49:EF:56:E3:97:BE:76:9E:C6:81:0C:2E:BD:49:D3:36:36:F2:00:1B:04";
+
+    #[test]
+    fn test_parse_fingerprint() {
+        let sha256 = parse_fingerprint(FINGERPRINT).unwrap();
+        assert_eq!(to_fingerprint(&sha256), FINGERPRINT);
+        let bare = FINGERPRINT.replace(':', "").to_lowercase();
+        assert_eq!(parse_fingerprint(&bare).unwrap(), sha256);
+
+        for bad in [
+            "",
+            "FC:68",
+            &FINGERPRINT.replace("FC", "XY"),
+            &FINGERPRINT.replace("FC", "+C"),
+            &format!("{bare}00"),
+        ] {
+            let error = parse_fingerprint(bad).unwrap_err();
+            assert!(matches!(error, ClientError::BadCertificate(_)), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let certificate = Certificate(b"not really DER".to_vec());
+        let verifier = PinnedCertificate {
+            sha256: Sha256::digest(&certificate.0).into(),
+        };
+        let verify = |certificate| {
+            verifier.verify_server_cert(
+                certificate,
+                &[],
+                &ServerName::try_from("vco01.example.com").unwrap(),
+                &mut std::iter::empty(),
+                &[],
+                SystemTime::now(),
+            )
+        };
+        assert!(verify(&certificate).is_ok());
+        assert!(verify(&Certificate(b"another certificate".to_vec())).is_err());
+    }
+}
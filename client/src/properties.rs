@@ -2,61 +2,33 @@
 
 use std::collections::BTreeMap as Map;
 
-use api_v1::date_time::DateTime;
-//use api_v1::property::SystemProperty;
-use api_v1::Number;
+use api_v1::property::GetSystemPropertiesResultItem;
 
 use crate::client::*;
 use crate::error::*;
-
-#[derive(Debug, PartialEq, Clone)]
-#[non_exhaustive]
-pub enum PropertyValue {
-    String(String),
-    Number(Number),
-    Boolean(bool),
-    Json(String),
-    Date(DateTime),
-    Datetime(DateTime),
-}
-
-pub struct Property {
-    pub name: String,
-    pub value: PropertyValue,
-    pub default_value: PropertyValue,
-    pub is_read_only: bool,
-    pub is_password: bool,
-    pub description: String,
-}
-
-impl Property {
-    // pub fn to_system_property(&self, id: Option<Integer>) -> SystemProperty {
-    //     todo!()
-    // }
-}
+use crate::model::SystemProperty;
 
 /// Extending Client with methods for handling VCO properties.
 impl Client {
     /// Gets the system properties.
-    pub async fn get_system_properties(
-        &self,
-    ) -> Result<Vec<GetSystemPropertiesResultItem>, ClientError> {
-        let resp = self
+    pub async fn get_system_properties(&self) -> Result<Vec<SystemProperty>, ClientError> {
+        self.require_operator("systemProperty/getSystemProperties")?;
+        let resp: Vec<GetSystemPropertiesResultItem> = self
             .post_without_payload("systemProperty/getSystemProperties")
             .await?;
-        Ok(resp)
+        Ok(resp.into_iter().map(SystemProperty::from).collect())
     }
 
     /// Gets the system properties, converting the result to a mapping by property name.
     ///
     pub async fn get_system_properties_map(
         &self,
-    ) -> Result<Map<String, GetSystemPropertiesResultItem>, ClientError> {
+    ) -> Result<Map<String, SystemProperty>, ClientError> {
         Ok(self
             .get_system_properties()
             .await?
             .into_iter()
-            .map(|item| (item.property.name.clone(), item))
+            .map(|property| (property.name.clone(), property))
             .collect())
     }
 
@@ -64,7 +36,7 @@ impl Client {
     //     todo!()
     // }
     //
-    // pub async fn set_system_property(&self, property: Property) -> Result<(), ClientError> {
+    // pub async fn set_system_property(&self, property: SystemProperty) -> Result<(), ClientError> {
     //     todo!()
     // }
 }
@@ -0,0 +1,170 @@
+//! Retrying API calls that fail for transient reasons.
+
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::error::ClientError;
+use crate::transport::Verb;
+
+/// Controls whether and how `Client` retries API calls that fail transiently: connection
+/// failures, HTTP 429/502/503/504 responses, and VCO "too many requests" errors.
+///
+/// Only read-only calls (API v1's `get*` methods, and API v2 `GET`s) are retried unless
+/// `retry_mutating` is set, since retrying a call that changes something could apply the change
+/// twice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// The most times a call is attempted, including the first. `1` disables retries.
+    pub max_attempts: u32,
+    /// The delay before the first retry.
+    pub initial_backoff: Duration,
+    /// The longest delay between attempts, including any `Retry-After` asked for by VCO.
+    pub max_backoff: Duration,
+    /// The factor the delay grows by after each attempt.
+    pub multiplier: f64,
+    /// Randomize each delay between half and all of its nominal value.
+    pub jitter: bool,
+    /// Also retry methods that may change state on VCO.
+    pub retry_mutating: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: true,
+            retry_mutating: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries. This is what `Client` uses unless told otherwise.
+    pub fn never() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Should a `verb` call to `method` that failed with `error` on attempt number `attempt`
+    /// (counting from 1) be tried again?
+    pub(crate) fn should_retry(
+        &self,
+        verb: Verb,
+        method: &str,
+        attempt: u32,
+        error: &ClientError,
+    ) -> bool {
+        attempt < self.max_attempts
+            && error.is_transient()
+            && (self.retry_mutating || is_read_only(verb, method))
+    }
+
+    /// How long to wait before the attempt after number `attempt` (counting from 1).
+    ///
+    /// A `Retry-After` from VCO takes precedence over the computed backoff.
+    pub(crate) fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after.min(self.max_backoff);
+        }
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        // Worked out in seconds as a float, which can't overflow, and capped before it's made a
+        // `Duration`, which can.
+        let growth = self.multiplier.max(1.0).powi(exponent);
+        let max_seconds = self.max_backoff.as_secs_f64();
+        let mut seconds = (self.initial_backoff.as_secs_f64() * growth).min(max_seconds);
+        if self.jitter {
+            seconds *= rand::thread_rng().gen_range(0.5..=1.0);
+        }
+        Duration::try_from_secs_f64(seconds).unwrap_or(self.max_backoff)
+    }
+}
+
+/// Methods whose name starts with `get`, e.g. `network/getNetworkGateways`, and API v2 `GET`s
+/// don't change anything on VCO so can always be retried safely.
+pub(crate) fn is_read_only(verb: Verb, method: &str) -> bool {
+    match verb {
+        Verb::Get => true,
+        Verb::Post => method
+            .rsplit('/')
+            .next()
+            .is_some_and(|name| name.starts_with("get")),
+        Verb::Put | Verb::Delete => false,
+    }
+}
+
+/// Parse an HTTP `Retry-After` header given in seconds.
+///
+/// VCO doesn't send the HTTP-date form, so that is ignored.
+pub(crate) fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod test_retry {
+    use super::*;
+
+    #[test]
+    fn test_is_read_only() {
+        assert!(is_read_only(Verb::Post, "network/getNetworkGateways"));
+        assert!(is_read_only(Verb::Post, "/metrics/getGatewayStatusMetrics"));
+        assert!(!is_read_only(Verb::Post, "gateway/updateGatewayAttributes"));
+        assert!(!is_read_only(Verb::Post, "login/operatorLogin"));
+        assert!(is_read_only(Verb::Get, "enterprises?limit=500"));
+        assert!(!is_read_only(Verb::Delete, "enterprises/abc/edges/def"));
+    }
+
+    #[test]
+    fn test_delay_backoff() {
+        let policy = RetryPolicy {
+            jitter: false,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.delay(1, None), Duration::from_millis(500));
+        assert_eq!(policy.delay(2, None), Duration::from_millis(1000));
+        assert_eq!(policy.delay(3, None), Duration::from_millis(2000));
+        assert_eq!(policy.delay(30, None), Duration::from_secs(30));
+
+        // However far the backoff grows, it's capped rather than overflowing.
+        assert_eq!(policy.delay(u32::MAX, None), Duration::from_secs(30));
+        let policy = RetryPolicy {
+            multiplier: f64::INFINITY,
+            ..policy
+        };
+        assert_eq!(policy.delay(2, None), Duration::from_secs(30));
+        let policy = RetryPolicy {
+            max_backoff: Duration::MAX,
+            jitter: true,
+            ..policy
+        };
+        assert!(policy.delay(100, None) > Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_delay_jitter_and_retry_after() {
+        let policy = RetryPolicy::default();
+        for _ in 0..100 {
+            let delay = policy.delay(2, None);
+            assert!(delay >= Duration::from_millis(500) && delay <= Duration::from_millis(1000));
+        }
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(7))),
+            Duration::from_secs(7)
+        );
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(700))),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after(" 12 "), Some(Duration::from_secs(12)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+    }
+}
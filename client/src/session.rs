@@ -0,0 +1,345 @@
+//! Saving a logged-in session so that it can be picked up again later, e.g. by the next run of a
+//! CLI tool, instead of logging in afresh.
+
+use std::fmt::{Debug, Formatter};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use reqwest::cookie::{CookieStore, Jar};
+use reqwest::header::HeaderValue;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ClientError;
+use crate::identity::Principal;
+
+/// The name of the cookie VCO keeps password-authenticated sessions in.
+pub const SESSION_COOKIE: &str = "velocloud.session";
+
+/// A password-authenticated session on VCO, which can be resumed with
+/// `VcoClientBuilder::resume_session` until it expires.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// The base URL of the VCO, as given by `Client::base_url`.
+    pub base_url: String,
+    pub username: String,
+    pub principal: Principal,
+    /// The value of the session cookie.
+    pub(crate) cookie: String,
+    /// When VCO said the session cookie expires, if it did.
+    pub expires: Option<SystemTime>,
+}
+
+impl Session {
+    /// Has the session cookie expired? VCO may still end the session earlier, e.g. when idle.
+    pub fn is_expired(&self) -> bool {
+        self.expires
+            .is_some_and(|expires| expires <= SystemTime::now())
+    }
+}
+
+impl Debug for Session {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Session")
+            .field("base_url", &self.base_url)
+            .field("username", &self.username)
+            .field("principal", &self.principal)
+            .field("cookie", &"****")
+            .field("expires", &self.expires)
+            .finish()
+    }
+}
+
+/// Saves sessions as files in a directory, one per VCO and user, readable only by the user.
+#[derive(Debug, Clone)]
+pub struct SessionCache {
+    dir: PathBuf,
+}
+
+impl SessionCache {
+    /// Keep sessions in `dir`, which is created when the first session is stored.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Keep sessions under the user's cache directory, e.g. `~/.cache/vco-rs/sessions` on Linux.
+    /// `None` if the platform doesn't have one.
+    pub fn in_user_cache_dir() -> Option<Self> {
+        dirs::cache_dir().map(|dir| Self::new(dir.join("vco-rs").join("sessions")))
+    }
+
+    /// Get the unexpired session for `username` on the VCO at `base_url`, if there is one.
+    /// Usernames are compared ignoring case, as VCO does.
+    ///
+    /// This is a cache, so a session that can't be read is treated as missing.
+    pub fn load(&self, base_url: &Url, username: &str) -> Option<Session> {
+        let text = fs::read_to_string(self.path(base_url.as_str(), username)).ok()?;
+        let session: Session = serde_json::from_str(&text).ok()?;
+        (session.base_url == base_url.as_str()
+            && session.username.eq_ignore_ascii_case(username)
+            && !session.is_expired())
+        .then_some(session)
+    }
+
+    /// Save `session`, replacing any saved session for the same VCO and user.
+    pub fn store(&self, session: &Session) -> Result<(), ClientError> {
+        create_private_dir(&self.dir).map_err(ClientError::SessionCache)?;
+        let text = serde_json::to_string(session).map_err(ClientError::Json)?;
+
+        // Write to a temporary file and move it into place, so that a reader never sees half a
+        // session.
+        let path = self.path(&session.base_url, &session.username);
+        let tmp_path = path.with_extension("tmp");
+        let mut file = create_private_file(&tmp_path).map_err(ClientError::SessionCache)?;
+        file.write_all(text.as_bytes())
+            .and_then(|_| file.sync_all())
+            .and_then(|_| fs::rename(&tmp_path, &path))
+            .map_err(ClientError::SessionCache)
+    }
+
+    /// Forget the saved session for `username` on the VCO at `base_url`, if there is one.
+    pub fn remove(&self, base_url: &Url, username: &str) -> Result<(), ClientError> {
+        match fs::remove_file(self.path(base_url.as_str(), username)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(ClientError::SessionCache(e)),
+            _ => Ok(()),
+        }
+    }
+
+    fn path(&self, base_url: &str, username: &str) -> PathBuf {
+        let name: String = format!("{base_url}_{}", username.to_ascii_lowercase())
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || "@.-".contains(c) {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{name}.json"))
+    }
+}
+
+/// Create `dir` readable only by the user. If it already exists, its permissions are set so.
+#[cfg(unix)]
+fn create_private_dir(dir: &std::path::Path) -> std::io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)?;
+    fs::set_permissions(dir, fs::Permissions::from_mode(0o700))
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &std::path::Path) -> std::io::Result<()> {
+    fs::create_dir_all(dir)
+}
+
+/// Create the file `path` readable only by the user. Anything already there, e.g. left by a run
+/// that crashed, is removed first, since the mode is only given to files that are created.
+#[cfg(unix)]
+fn create_private_file(path: &std::path::Path) -> std::io::Result<fs::File> {
+    use std::os::unix::fs::OpenOptionsExt;
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+}
+
+#[cfg(not(unix))]
+fn create_private_file(path: &std::path::Path) -> std::io::Result<fs::File> {
+    fs::File::create(path)
+}
+
+/// The value and expiry of VCO's session cookie.
+#[derive(Clone)]
+pub(crate) struct SessionCookie {
+    pub(crate) value: String,
+    pub(crate) expires: Option<SystemTime>,
+}
+
+impl Debug for SessionCookie {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SessionCookie(****, {:?})", self.expires)
+    }
+}
+
+/// A `Client`'s cookie store: a plain `Jar`, which also notes VCO's session cookie as it goes
+/// past so that the session can be saved.
+#[derive(Default)]
+pub(crate) struct SessionCookies {
+    jar: Jar,
+    session: Mutex<Option<SessionCookie>>,
+}
+
+impl SessionCookies {
+    /// The session cookie, if VCO has set one.
+    pub(crate) fn session_cookie(&self) -> Option<SessionCookie> {
+        self.session.lock().unwrap().clone()
+    }
+
+    /// Send `cookie` as the session cookie to `url` from now on.
+    pub(crate) fn set_session_cookie(&self, cookie: SessionCookie, url: &Url) {
+        self.jar
+            .add_cookie_str(&format!("{SESSION_COOKIE}={}", cookie.value), url);
+        *self.session.lock().unwrap() = Some(cookie);
+    }
+}
+
+// `Jar`'s own `Debug` would give the cookies away.
+impl Debug for SessionCookies {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SessionCookies({:?})", self.session_cookie())
+    }
+}
+
+impl CookieStore for SessionCookies {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let cookie_headers: Vec<&HeaderValue> = cookie_headers.collect();
+        for header in &cookie_headers {
+            let Some(cookie) = header
+                .to_str()
+                .ok()
+                .and_then(|header| cookie::Cookie::parse(header).ok())
+            else {
+                continue;
+            };
+            if cookie.name() != SESSION_COOKIE {
+                continue;
+            }
+            let expires = match (cookie.max_age(), cookie.expires_datetime()) {
+                (Some(max_age), _) => {
+                    // A negative Max-Age has expired, and one too far off to add to now is taken
+                    // as no known expiry.
+                    let max_age = std::time::Duration::try_from(max_age).unwrap_or_default();
+                    SystemTime::now().checked_add(max_age)
+                }
+                (None, Some(expires)) => Some(expires.into()),
+                (None, None) => None,
+            };
+            // An empty or already-expired cookie is VCO ending the session.
+            let ended =
+                cookie.value().is_empty() || expires.is_some_and(|e| e <= SystemTime::now());
+            *self.session.lock().unwrap() = (!ended).then(|| SessionCookie {
+                value: cookie.value().into(),
+                expires,
+            });
+        }
+        self.jar.set_cookies(&mut cookie_headers.into_iter(), url);
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        self.jar.cookies(url)
+    }
+}
+
+#[cfg(test)]
+mod test_session {
+    use super::*;
+    use std::time::Duration;
+
+    fn session(expires: Option<SystemTime>) -> Session {
+        Session {
+            base_url: "https://vco01.example.com/".into(),
+            username: "alice@example.com".into(),
+            principal: Principal::Operator,
+            cookie: "s3cr3t".into(),
+            expires,
+        }
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let dir = std::env::temp_dir().join(format!("vco-rs-test-sessions-{}", std::process::id()));
+        let cache = SessionCache::new(&dir);
+        let url = Url::parse("https://vco01.example.com/").unwrap();
+        let username = "alice@example.com";
+
+        assert!(cache.load(&url, username).is_none());
+        let saved = session(Some(SystemTime::now() + Duration::from_secs(600)));
+        cache.store(&saved).unwrap();
+        assert_eq!(cache.load(&url, username), Some(saved));
+        assert!(cache.load(&url, "bob@example.com").is_none());
+        assert!(cache.load(&url, "Alice@Example.com").is_some());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+            let path = cache.path(url.as_str(), username);
+            assert_eq!(mode(&path), 0o600);
+
+            // Nor do a readable directory, or a temporary file left by a crash, let anyone else
+            // read the session.
+            let readable = fs::Permissions::from_mode(0o755);
+            fs::set_permissions(&dir, readable.clone()).unwrap();
+            let tmp_path = path.with_extension("tmp");
+            fs::write(&tmp_path, "").unwrap();
+            fs::set_permissions(&tmp_path, readable).unwrap();
+            cache
+                .store(&session(Some(SystemTime::now() + Duration::from_secs(600))))
+                .unwrap();
+            assert_eq!(mode(&dir), 0o700);
+            assert_eq!(mode(&path), 0o600);
+        }
+
+        // Expired sessions aren't returned.
+        cache
+            .store(&session(Some(SystemTime::now() - Duration::from_secs(1))))
+            .unwrap();
+        assert!(cache.load(&url, username).is_none());
+
+        cache.remove(&url, username).unwrap();
+        cache.remove(&url, username).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_session_cookie_noted() {
+        let cookies = SessionCookies::default();
+        let url = Url::parse("https://vco01.example.com/portal/rest/login/operatorLogin").unwrap();
+        let headers = [
+            HeaderValue::from_static("other=1; Path=/"),
+            HeaderValue::from_static("velocloud.session=abc123; Path=/; Max-Age=3600; HttpOnly"),
+        ];
+        cookies.set_cookies(&mut headers.iter(), &url);
+
+        let cookie = cookies.session_cookie().unwrap();
+        assert_eq!(cookie.value, "abc123");
+        assert!(cookie.expires.unwrap() > SystemTime::now() + Duration::from_secs(3500));
+        assert!(cookies
+            .cookies(&url)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .contains("velocloud.session=abc123"));
+
+        // The session's debug output doesn't give the cookie away.
+        assert!(!format!("{cookies:?}").contains("abc123"));
+
+        // A Max-Age too big to say when it ends is as good as none.
+        let headers = [HeaderValue::from_static(
+            "velocloud.session=def456; Path=/; Max-Age=99999999999999999999",
+        )];
+        cookies.set_cookies(&mut headers.iter(), &url);
+        let cookie = cookies.session_cookie().unwrap();
+        assert_eq!(cookie.value, "def456");
+        assert_eq!(cookie.expires, None);
+
+        // VCO clears the cookie on logout.
+        let headers = [HeaderValue::from_static(
+            "velocloud.session=; Path=/; Max-Age=0",
+        )];
+        cookies.set_cookies(&mut headers.iter(), &url);
+        assert!(cookies.session_cookie().is_none());
+    }
+}
@@ -0,0 +1,234 @@
+//! Client-side pacing of API calls, so that fanning out many calls doesn't get us throttled by
+//! VCO.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+use crate::error::ClientError;
+
+/// Limits the rate of API calls with a token bucket, and the number of calls in flight at once
+/// with a semaphore. Every call made through `Client` waits here first; retries count as calls.
+///
+/// Cloning a `Throttle` gives a handle to the same limits, so several `Client`s talking to the
+/// same orchestrator can share one. The default `Throttle` doesn't limit anything.
+#[derive(Debug, Clone, Default)]
+pub struct Throttle {
+    bucket: Option<Arc<Mutex<TokenBucket>>>,
+    in_flight: Option<Arc<Semaphore>>,
+}
+
+/// Builds a `Throttle`. Limits that aren't set aren't applied.
+#[derive(Debug, Clone, Default)]
+pub struct ThrottleBuilder {
+    rate_limit: Option<(f64, u32)>,
+    max_in_flight: Option<usize>,
+}
+
+/// Held for the duration of an API call; lets the next queued call go when dropped.
+#[derive(Debug)]
+pub(crate) struct ThrottlePermit {
+    _in_flight: Option<OwnedSemaphorePermit>,
+}
+
+impl Throttle {
+    pub fn builder() -> ThrottleBuilder {
+        ThrottleBuilder::default()
+    }
+
+    /// Wait until the limits allow another call to be made.
+    pub(crate) async fn acquire(&self) -> ThrottlePermit {
+        // Take the in-flight slot first, so that calls waiting for a slot don't use up tokens.
+        let in_flight = match &self.in_flight {
+            Some(semaphore) => Some(
+                semaphore
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .expect("Throttle semaphore is never closed"),
+            ),
+            None => None,
+        };
+
+        if let Some(bucket) = &self.bucket {
+            let wait = bucket.lock().await.reserve(Instant::now());
+            if !wait.is_zero() {
+                tokio::time::sleep(wait).await;
+            }
+        }
+
+        ThrottlePermit {
+            _in_flight: in_flight,
+        }
+    }
+}
+
+impl ThrottleBuilder {
+    /// Allow a sustained `requests_per_second`, with bursts of up to `burst` calls.
+    pub fn rate_limit(mut self, requests_per_second: f64, burst: u32) -> Self {
+        self.rate_limit = Some((requests_per_second, burst));
+        self
+    }
+
+    /// Allow at most `max_in_flight` calls to be waiting on VCO at once.
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = Some(max_in_flight);
+        self
+    }
+
+    /// Build the `Throttle`.
+    ///
+    /// Fails with `ClientError::BadThrottle` if the rate isn't positive or `max_in_flight` is zero,
+    /// since no calls could ever be made, or if `max_in_flight` is more than
+    /// `Semaphore::MAX_PERMITS`.
+    pub fn build(self) -> Result<Throttle, ClientError> {
+        let bucket = match self.rate_limit {
+            Some((rate, burst)) if rate > 0.0 => {
+                Some(Arc::new(Mutex::new(TokenBucket::new(rate, burst.max(1)))))
+            }
+            Some((rate, _)) => {
+                return Err(ClientError::BadThrottle(format!(
+                    "the rate must be positive, not {rate}"
+                )))
+            }
+            None => None,
+        };
+        let in_flight = match self.max_in_flight {
+            Some(0) => {
+                return Err(ClientError::BadThrottle(
+                    "max_in_flight must be at least 1".into(),
+                ))
+            }
+            Some(max) if max > Semaphore::MAX_PERMITS => {
+                return Err(ClientError::BadThrottle(format!(
+                    "max_in_flight must be at most {}, not {max}",
+                    Semaphore::MAX_PERMITS
+                )))
+            }
+            Some(max) => Some(Arc::new(Semaphore::new(max))),
+            None => None,
+        };
+        Ok(Throttle { bucket, in_flight })
+    }
+}
+
+/// A token bucket that lets callers reserve tokens ahead of time, queueing them fairly.
+#[derive(Debug)]
+struct TokenBucket {
+    /// Tokens added per second.
+    rate: f64,
+    /// The most tokens the bucket holds.
+    capacity: f64,
+    /// Tokens currently available. Negative when calls are queued waiting for tokens.
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, burst: u32) -> Self {
+        Self {
+            rate,
+            capacity: burst as f64,
+            tokens: burst as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Take a token, returning how long to wait before it can be used.
+    fn reserve(&mut self, now: Instant) -> Duration {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = now;
+
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            // A rate small enough can make the wait too long for a `Duration`.
+            Duration::try_from_secs_f64(-self.tokens / self.rate).unwrap_or(Duration::MAX)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_throttle {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_reserve() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(10.0, 2);
+        bucket.last_refill = start;
+
+        // The burst is available straight away...
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        // ...then callers queue up a tenth of a second apart.
+        assert_eq!(bucket.reserve(start), Duration::from_millis(100));
+        assert_eq!(bucket.reserve(start), Duration::from_millis(200));
+
+        // Tokens refill over time, but never beyond the burst.
+        let later = start + Duration::from_secs(10);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert!(bucket.reserve(later) > Duration::ZERO);
+    }
+
+    #[test]
+    fn test_token_bucket_tiny_rate() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(1e-20, 1);
+        bucket.last_refill = start;
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::MAX);
+    }
+
+    #[tokio::test]
+    async fn test_max_in_flight() {
+        let throttle = Throttle::builder().max_in_flight(2).build().unwrap();
+        let first = throttle.acquire().await;
+        let _second = throttle.acquire().await;
+
+        // A third call has to wait for one of the others to finish.
+        let third = tokio::time::timeout(Duration::from_millis(50), throttle.acquire()).await;
+        assert!(third.is_err());
+
+        drop(first);
+        let third = tokio::time::timeout(Duration::from_millis(50), throttle.acquire()).await;
+        assert!(third.is_ok());
+    }
+
+    #[test]
+    fn test_bad_limits() {
+        for rate in [0.0, -1.0, f64::NAN] {
+            assert!(matches!(
+                Throttle::builder().rate_limit(rate, 1).build(),
+                Err(ClientError::BadThrottle(_))
+            ));
+        }
+        for max in [0, Semaphore::MAX_PERMITS + 1, usize::MAX] {
+            assert!(matches!(
+                Throttle::builder().max_in_flight(max).build(),
+                Err(ClientError::BadThrottle(_))
+            ));
+        }
+        assert!(Throttle::builder()
+            .max_in_flight(Semaphore::MAX_PERMITS)
+            .build()
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_unlimited() {
+        let throttle = Throttle::default();
+        let mut permits = vec![];
+        for _ in 0..100 {
+            permits.push(throttle.acquire().await);
+        }
+        assert_eq!(permits.len(), 100);
+    }
+}
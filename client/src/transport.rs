@@ -0,0 +1,208 @@
+//! The wire protocols that `Client` can use to make API calls.
+//!
+//! VCO accepts the same method names, parameters and results over either its REST API or its
+//! JSON-RPC API; only the "wrapper" differs. `Transport` hides that difference from the typed
+//! methods on `Client`.
+//!
+//! API v2 is different again: resources rather than methods, and HTTP verbs other than `POST`.
+//! It has a `Transport` of its own, which `Client` uses for the resources it has when VCO is
+//! new enough.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use api_v1::jsonrpc::{JsonRpcRequest, JsonRpcResponse};
+
+use crate::client::Client;
+use crate::error::ClientError;
+
+/// Selects how `Client` talks to VCO.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum Transport {
+    /// `POST {base_url}/portal/rest/{method}`, with the params as the body.
+    #[default]
+    Rest,
+
+    /// `POST {base_url}/portal/`, with the method and params in a JSON-RPC 2.0 envelope.
+    JsonRpc,
+
+    /// `{verb} {base_url}/api/sdwan/v2/{resource}`, with any params as the body.
+    ///
+    /// This only carries API v2's resources. API v1 methods are sent over `Rest` if it's chosen
+    /// with `Client::with_transport`.
+    RestV2,
+}
+
+/// The HTTP method a call is made with. API v1 calls are always `POST`s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Verb {
+    Get,
+    #[default]
+    Post,
+    Put,
+    Delete,
+}
+
+impl Verb {
+    pub(crate) fn is_post(&self) -> bool {
+        *self == Verb::Post
+    }
+}
+
+impl From<Verb> for reqwest::Method {
+    fn from(verb: Verb) -> Self {
+        match verb {
+            Verb::Get => reqwest::Method::GET,
+            Verb::Post => reqwest::Method::POST,
+            Verb::Put => reqwest::Method::PUT,
+            Verb::Delete => reqwest::Method::DELETE,
+        }
+    }
+}
+
+impl Transport {
+    /// The URL path (after the host) that a call to `method` is sent to. For `RestV2`, `method`
+    /// is the resource, with any query.
+    pub(crate) fn url_path(&self, method: &str) -> String {
+        match self {
+            Transport::Rest => format!("{}/{}", api_v1::API_BASE, method),
+            Transport::JsonRpc => api_v1::JSONRPC_BASE.to_string(),
+            Transport::RestV2 => format!("{}/{}", api_v2::API_BASE, method),
+        }
+    }
+
+    /// The transport API v1 methods are sent over when this one is chosen.
+    pub(crate) fn for_api_v1(self) -> Self {
+        match self {
+            Transport::RestV2 => Transport::Rest,
+            transport => transport,
+        }
+    }
+
+    /// Build the body of the request, if there is one.
+    pub(crate) fn encode(
+        &self,
+        method: &str,
+        params: Option<Value>,
+        id: u64,
+    ) -> Result<Option<String>, ClientError> {
+        match self {
+            Transport::Rest | Transport::RestV2 => params
+                .map(|params| serde_json::to_string(&params).map_err(ClientError::Json))
+                .transpose(),
+            Transport::JsonRpc => {
+                // VCO expects `params` to always be present, if only as an empty object.
+                let params = params.unwrap_or_else(|| Value::Object(Default::default()));
+                let request = JsonRpcRequest::new(method, params, id);
+                serde_json::to_string(&request)
+                    .map(Some)
+                    .map_err(ClientError::Json)
+            }
+        }
+    }
+
+    /// Interpret the body text of a response, returning the result object or the API error.
+    ///
+    /// An empty body is treated as `null`.
+    pub(crate) fn decode(&self, text: &str) -> Result<Value, ClientError> {
+        if text.is_empty() {
+            return Ok(Value::Null);
+        }
+
+        // Interpret the body of the response as JSON.
+        let json: Value = serde_json::from_str(text).map_err(ClientError::Json)?;
+
+        // Both REST and JSON-RPC put errors in a top-level "error" key.
+        if let Some(api_error) = Client::identify_error_body(&json) {
+            return Err(ClientError::Api(Box::new(api_error)));
+        }
+
+        match self {
+            Transport::Rest | Transport::RestV2 => Ok(json),
+            Transport::JsonRpc => {
+                let response: JsonRpcResponse =
+                    serde_json::from_value(json).map_err(ClientError::Json)?;
+                Ok(response.result.unwrap_or(Value::Null))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_transport {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_url_path() {
+        assert_eq!(
+            Transport::Rest.url_path("network/getNetworkGateways"),
+            "portal/rest/network/getNetworkGateways"
+        );
+        assert_eq!(
+            Transport::JsonRpc.url_path("network/getNetworkGateways"),
+            "portal/"
+        );
+        assert_eq!(
+            Transport::RestV2.url_path("enterprises?limit=10"),
+            "api/sdwan/v2/enterprises?limit=10"
+        );
+        assert_eq!(Transport::RestV2.for_api_v1(), Transport::Rest);
+    }
+
+    #[test]
+    fn test_encode_jsonrpc() {
+        let body = Transport::JsonRpc
+            .encode("network/getNetworkGateways", None, 3)
+            .unwrap()
+            .unwrap();
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            body,
+            json!({"jsonrpc": "2.0", "method": "network/getNetworkGateways", "params": {}, "id": 3})
+        );
+        assert!(Transport::Rest.encode("a/b", None, 3).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_decode_jsonrpc() {
+        let result = Transport::JsonRpc
+            .decode(r#"{"jsonrpc":"2.0","result":[{"id":1}],"id":3}"#)
+            .unwrap();
+        assert_eq!(result, json!([{"id": 1}]));
+
+        let error = Transport::JsonRpc
+            .decode(r#"{"jsonrpc":"2.0","error":{"code":-32603,"message":"boom"},"id":3}"#)
+            .unwrap_err();
+        assert_eq!(error.api_error().map(|e| e.code), Some(-32603));
+    }
+
+    #[test]
+    fn test_decode_unrecognized_error() {
+        // However it's shaped, an error is never a result.
+        for transport in [Transport::Rest, Transport::JsonRpc] {
+            let error = transport
+                .decode(r#"{"jsonrpc":"2.0","error":{"code":-32001,"detail":"x"},"id":3}"#)
+                .unwrap_err();
+            let error = error.api_error().unwrap();
+            assert_eq!(error.code, -32001);
+            assert_eq!(error.the_rest.get("detail"), Some(&json!("x")));
+
+            let error = transport.decode(r#"{"error":"boom"}"#).unwrap_err();
+            assert_eq!(error.api_error().unwrap().message, "boom");
+
+            let error = transport.decode(r#"{"error":[1,2]}"#).unwrap_err();
+            let error = error.api_error().unwrap();
+            assert_eq!(error.code, api_v1::error::SERVER_ERROR);
+            assert_eq!(error.the_rest.get("error"), Some(&json!([1, 2])));
+        }
+
+        let result = Transport::JsonRpc
+            .decode(r#"{"jsonrpc":"2.0","result":1,"error":null,"id":3}"#)
+            .unwrap();
+        assert_eq!(result, json!(1));
+    }
+}
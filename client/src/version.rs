@@ -0,0 +1,107 @@
+//! Which release of VCO a `Client` is talking to.
+//!
+//! A `Client` finds out VCO's version when it logs in. There's no table of which release first
+//! has each API method: none of the methods `Client` calls has a sourced first release, and a
+//! wrong entry would refuse calls VCO would have taken. So methods aren't checked against the
+//! version before they're sent. A method VCO says it doesn't have fails with
+//! `ClientError::Unsupported` instead.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::error::ClientError;
+
+/// A VCO release, e.g. "4.5.1" or "5.2.0.3". Releases compare in the order they came out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VcoVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    /// The fourth number some releases have, or `0`.
+    pub build: u32,
+}
+
+impl VcoVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            build: 0,
+        }
+    }
+
+    /// Parse a version such as "4.5.1", as VCO gives it in its `product.version` property. From
+    /// two to four numbers are accepted; missing ones are `0`.
+    pub fn parse(value: &str) -> Result<Self, ClientError> {
+        let bad_version = || ClientError::BadVersion(value.into());
+        let parts = value
+            .trim()
+            .split('.')
+            .map(|part| part.parse::<u32>().map_err(|_| bad_version()))
+            .collect::<Result<Vec<_>, _>>()?;
+        if !(2..=4).contains(&parts.len()) {
+            return Err(bad_version());
+        }
+        let part = |i: usize| parts.get(i).copied().unwrap_or(0);
+        Ok(Self {
+            major: part(0),
+            minor: part(1),
+            patch: part(2),
+            build: part(3),
+        })
+    }
+}
+
+impl Display for VcoVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if self.build != 0 {
+            write!(f, ".{}", self.build)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for VcoVersion {
+    type Err = ClientError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// The system property holding VCO's version.
+pub(crate) const VERSION_PROPERTY: &str = "product.version";
+
+#[cfg(test)]
+mod test_version {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            VcoVersion::parse("4.5.1").unwrap(),
+            VcoVersion::new(4, 5, 1)
+        );
+        assert_eq!(VcoVersion::parse("5.2").unwrap(), VcoVersion::new(5, 2, 0));
+        let version = VcoVersion::parse("5.2.0.3").unwrap();
+        assert_eq!(version.build, 3);
+        assert_eq!(version.to_string(), "5.2.0.3");
+        assert_eq!(VcoVersion::new(4, 5, 1).to_string(), "4.5.1");
+
+        for bad in ["", "4", "4.5.x", "4.5.1.2.3", "v4.5"] {
+            assert!(
+                matches!(VcoVersion::parse(bad), Err(ClientError::BadVersion(_))),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_order() {
+        let versions = ["3.4.2", "4.5.1", "4.5.1.1", "4.10.0", "5.0.0"]
+            .map(|version| version.parse::<VcoVersion>().unwrap());
+        assert!(versions.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
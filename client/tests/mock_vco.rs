@@ -0,0 +1,786 @@
+//! Tests of `Client` against the mock VCO, covering the HTTP layer end to end.
+
+use std::net::Ipv4Addr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use client::audit::FieldAudit;
+use client::builder::VcoClientBuilder;
+use client::cassette::Cassette;
+use client::client::{Client, DeserializeProfile};
+use client::credentials::{CredentialProvider, Credentials};
+use client::error::ClientError;
+use client::id::{EnterpriseId, GatewayId, NetworkId};
+use client::identity::Principal;
+use client::model::{
+    GatewayAttributes, GatewayExpansion, GatewayPool, GatewayPoolAttributes, GatewayPoolProvision,
+    GatewayProvision, HandoffType, Metric, PropertyValue, ServiceState,
+};
+use client::retry::RetryPolicy;
+use client::transport::Transport;
+use client::version::VcoVersion;
+use client::Url;
+use mock_vco::{Fault, MockVco};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+fn builder(vco: &MockVco) -> VcoClientBuilder {
+    VcoClientBuilder::from_url(Url::parse(&vco.base_url()).unwrap())
+}
+
+async fn operator(vco: &MockVco) -> Client {
+    builder(vco)
+        .login_password(mock_vco::OPERATOR_USERNAME, mock_vco::OPERATOR_PASSWORD)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_login_password() {
+    let vco = MockVco::start().await;
+    let client = operator(&vco).await;
+    let identity = client.identity().unwrap();
+    assert_eq!(identity.username, mock_vco::OPERATOR_USERNAME);
+    assert_eq!(identity.principal, Principal::Operator);
+    assert!(client.session().is_some());
+
+    let error = builder(&vco)
+        .login_password(mock_vco::OPERATOR_USERNAME, "wrong")
+        .await
+        .unwrap_err();
+    assert!(matches!(error, ClientError::AuthFailed(_)), "{error:?}");
+}
+
+#[tokio::test]
+async fn test_login_token() {
+    let vco = MockVco::start().await;
+    let client = builder(&vco)
+        .login_token(mock_vco::OPERATOR_TOKEN)
+        .await
+        .unwrap();
+    assert_eq!(client.principal(), Some(Principal::Operator));
+    assert!(client.session().is_none());
+
+    let error = builder(&vco).login_token("expired").await.unwrap_err();
+    assert!(matches!(error, ClientError::AuthFailed(_)), "{error:?}");
+}
+
+#[tokio::test]
+async fn test_pinned_certificate() {
+    let vco = MockVco::start_tls().await;
+    let client = builder(&vco)
+        .pin_certificate_sha256(mock_vco::TLS_CERTIFICATE_SHA256)
+        .login_token(mock_vco::OPERATOR_TOKEN)
+        .await
+        .unwrap();
+    assert_eq!(client.principal(), Some(Principal::Operator));
+
+    // The mock's certificate is self-signed, so it's only trusted when pinned.
+    let error = builder(&vco)
+        .login_token(mock_vco::OPERATOR_TOKEN)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, ClientError::Request(_)), "{error:?}");
+
+    let wrong_pin = mock_vco::TLS_CERTIFICATE_SHA256.replace("FC", "00");
+    let error = builder(&vco)
+        .pin_certificate_sha256(&wrong_pin)
+        .login_password(mock_vco::OPERATOR_USERNAME, mock_vco::OPERATOR_PASSWORD)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, ClientError::Request(_)), "{error:?}");
+    assert!(
+        format!("{error:?}").contains("pinned fingerprint"),
+        "{error:?}"
+    );
+    // The handshake failed before the password was sent.
+    assert_eq!(vco.calls("login/operatorLogin"), 0);
+}
+
+#[tokio::test]
+async fn test_enterprise_and_partner_login() {
+    let vco = MockVco::start().await;
+    let client = builder(&vco)
+        .login_enterprise_password(mock_vco::ENTERPRISE_USERNAME, mock_vco::ENTERPRISE_PASSWORD)
+        .await
+        .unwrap();
+    assert_eq!(client.principal(), Some(Principal::Enterprise));
+    assert_eq!(
+        client.identity().unwrap().enterprise_id,
+        Some(EnterpriseId(12))
+    );
+
+    // Operator-only methods fail without bothering VCO.
+    let error = client.get_network_gateways().await.unwrap_err();
+    assert!(matches!(error, ClientError::OperatorRequired { .. }));
+    assert_eq!(vco.calls("network/getNetworkGateways"), 0);
+
+    let client = builder(&vco)
+        .login_enterprise_password(mock_vco::PARTNER_USERNAME, mock_vco::PARTNER_PASSWORD)
+        .await
+        .unwrap();
+    assert_eq!(client.principal(), Some(Principal::Partner));
+
+    // Operators can't log in as enterprise users.
+    let error = builder(&vco)
+        .login_enterprise_password(mock_vco::OPERATOR_USERNAME, mock_vco::OPERATOR_PASSWORD)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, ClientError::AuthFailed(_)));
+}
+
+#[tokio::test]
+async fn test_api_calls() {
+    let vco = MockVco::start().await;
+    let client = operator(&vco).await;
+
+    let gateways = client.get_network_gateways().await.unwrap();
+    assert_eq!(gateways.len(), 2);
+    assert_eq!(gateways[0].name, "vcg01-lon");
+
+    let properties = client.get_system_properties_map().await.unwrap();
+    assert_eq!(properties["product.version"].value.to_string(), "4.5.1");
+
+    vco.set_fixture("network/getNetworkGateways", serde_json::json!([]));
+    assert!(client.get_network_gateways().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_gateway_lifecycle() {
+    let vco = MockVco::start().await;
+    let client = operator(&vco).await;
+
+    let new = client
+        .insert_gateway(&GatewayProvision {
+            network_id: NetworkId(1),
+            name: "vcg03-sin".into(),
+            ip_address: Ipv4Addr::new(203, 0, 113, 30),
+            description: Some("Singapore gateway".into()),
+            site: None,
+            gateway_pool_id: None,
+            hand_off_detail: None,
+        })
+        .await
+        .unwrap();
+    assert!(!new.activation_key.is_empty());
+    let gateway = client.get_gateway(new.id).await.unwrap().unwrap();
+    assert_eq!(gateway.name, "vcg03-sin");
+    assert_eq!(gateway.logical_id, new.logical_id);
+    assert_eq!(gateway.service_state, ServiceState::OutOfService);
+
+    let attributes = GatewayAttributes {
+        name: Some("vcg03-sgp".into()),
+        ..Default::default()
+    };
+    client
+        .update_gateway_attributes(new.id, attributes)
+        .await
+        .unwrap();
+    client.put_gateway_in_service(new.id).await.unwrap();
+    let gateway = client.get_gateway(new.id).await.unwrap().unwrap();
+    assert_eq!(gateway.name, "vcg03-sgp");
+    assert_eq!(gateway.service_state, ServiceState::InService);
+    client.quiesce_gateway(new.id).await.unwrap();
+    let gateway = client.get_gateway(new.id).await.unwrap().unwrap();
+    assert_eq!(gateway.service_state, ServiceState::Quiesced);
+    client.put_gateway_out_of_service(new.id).await.unwrap();
+    assert_eq!(vco.calls("gateway/updateGatewayAttributes"), 4);
+
+    client.delete_gateway(new.id).await.unwrap();
+    assert_eq!(client.get_gateway(new.id).await.unwrap(), None);
+    // VCO says a gateway that's gone changed nothing, rather than giving an error.
+    let error = client.delete_gateway(new.id).await.unwrap_err();
+    assert!(matches!(error, ClientError::NotFound { .. }), "{error:?}");
+    assert!(error.is_not_found());
+    let error = client.quiesce_gateway(new.id).await.unwrap_err();
+    assert!(error.is_not_found(), "{error:?}");
+}
+
+#[tokio::test]
+async fn test_gateway_certificates() {
+    let vco = MockVco::start().await;
+    let client = operator(&vco).await;
+
+    // Without the expansion there are none.
+    let gateways = client.get_network_gateways().await.unwrap();
+    assert!(gateways
+        .iter()
+        .all(|gateway| gateway.certificates.is_none()));
+
+    let gateways = client.get_gateway_certificates().await.unwrap();
+    let certificates = gateways[0].certificates.as_ref().unwrap();
+    assert_eq!(certificates.len(), 1);
+    assert_eq!(certificates[0].gateway_id, gateways[0].id);
+    let details = certificates[0].details.as_ref().unwrap();
+    assert!(details.subject.ends_with("CN=vcg01-lon.example.com"));
+    assert_eq!(details.subject_alt_names.len(), 2);
+
+    let when = OffsetDateTime::parse("2023-06-18T12:00:00Z", &Rfc3339).unwrap();
+    let expiring = gateways
+        .iter()
+        .flat_map(|gateway| gateway.certificates.iter().flatten())
+        .filter(|certificate| certificate.expires_by(when + time::Duration::days(30)))
+        .collect::<Vec<_>>();
+    assert_eq!(expiring.len(), 1);
+    assert_eq!(expiring[0].gateway_id, GatewayId(81));
+}
+
+#[tokio::test]
+async fn test_gateway_status_metrics() {
+    let vco = MockVco::start().await;
+    let client = operator(&vco).await;
+
+    let start = OffsetDateTime::parse("2023-06-18T12:00:00Z", &Rfc3339).unwrap();
+    let series = client
+        .get_gateway_status_metrics(GatewayId(80), start, None, &[Metric::CpuPct])
+        .await
+        .unwrap();
+    assert_eq!(series.metrics.len(), 1);
+    let cpu_pct = series.get(Metric::CpuPct).unwrap();
+    assert_eq!(cpu_pct.points[0].time, start);
+    assert_eq!(cpu_pct.summary.max, Some(12.25));
+
+    let series = client
+        .get_gateway_status_metrics(GatewayId(80), start, None, Metric::ALL)
+        .await
+        .unwrap();
+    assert_eq!(series.metrics.len(), Metric::ALL.len());
+    assert_eq!(series.get(Metric::TunnelCountV6).unwrap().points.len(), 4);
+}
+
+#[tokio::test]
+async fn test_gateway_pools() {
+    let vco = MockVco::start().await;
+    let client = operator(&vco).await;
+
+    let pools = client.get_gateway_pools().await.unwrap();
+    assert_eq!(pools.len(), 2);
+    assert_eq!(pools[0].hand_off_type, HandoffType::Allow);
+    assert_eq!(pools[0].gateways.as_ref().unwrap().len(), 2);
+    assert_eq!(
+        pools[0].gateways.as_ref().unwrap()[0].gateway_pool_assoc_id,
+        Some(101)
+    );
+
+    let pool_id = client
+        .insert_gateway_pool(&GatewayPoolProvision {
+            network_id: NetworkId(1),
+            name: "EU Pool".into(),
+            description: None,
+            hand_off_type: Some(HandoffType::None),
+        })
+        .await
+        .unwrap();
+    let attributes = GatewayPoolAttributes {
+        description: Some("Gateways in Europe".into()),
+        ..Default::default()
+    };
+    client
+        .update_gateway_pool(pool_id, attributes)
+        .await
+        .unwrap();
+
+    // Move London into the new pool, and Acme with it.
+    let london = GatewayId(80);
+    client.add_gateway_to_pool(pool_id, london).await.unwrap();
+    client
+        .remove_gateway_from_pool(pools[0].id, london)
+        .await
+        .unwrap();
+    assert!(client.add_gateway_to_pool(pool_id, london).await.is_err());
+    let error = client
+        .remove_gateway_from_pool(pools[0].id, london)
+        .await
+        .unwrap_err();
+    assert!(error.is_not_found(), "{error:?}");
+    let error = client
+        .set_enterprise_gateway_pool(EnterpriseId(999), pool_id)
+        .await
+        .unwrap_err();
+    assert!(error.is_not_found(), "{error:?}");
+    client
+        .set_enterprise_gateway_pool(EnterpriseId(12), pool_id)
+        .await
+        .unwrap();
+
+    let pools = client.get_gateway_pools().await.unwrap();
+    let pool = pools.iter().find(|pool| pool.id == pool_id).unwrap();
+    assert_eq!(pool.description.as_deref(), Some("Gateways in Europe"));
+    let names = |pool: &GatewayPool| -> Vec<String> {
+        let gateways = pool.gateways.as_ref().unwrap();
+        gateways
+            .iter()
+            .map(|gateway| gateway.name.clone())
+            .collect()
+    };
+    assert_eq!(names(pool), ["vcg01-lon"]);
+    assert_eq!(names(&pools[0]), ["vcg02-nyc"]);
+    let enterprises = client.get_enterprises().await.unwrap();
+    assert_eq!(enterprises[0].gateway_pool_id, pool_id);
+
+    // A pool can't be deleted while an enterprise is using it.
+    assert!(client.delete_gateway_pool(pool_id).await.is_err());
+    client
+        .set_enterprise_gateway_pool(EnterpriseId(12), pools[0].id)
+        .await
+        .unwrap();
+    client.delete_gateway_pool(pool_id).await.unwrap();
+    assert_eq!(client.get_gateway_pools().await.unwrap().len(), 2);
+
+    // VCO says a pool that's gone changed nothing, rather than giving an error.
+    let error = client.delete_gateway_pool(pool_id).await.unwrap_err();
+    assert!(error.is_not_found(), "{error:?}");
+    let attributes = GatewayPoolAttributes::default();
+    let error = client
+        .update_gateway_pool(pool_id, attributes)
+        .await
+        .unwrap_err();
+    assert!(error.is_not_found(), "{error:?}");
+}
+
+#[tokio::test]
+async fn test_enterprises_and_edges() {
+    let vco = MockVco::start().await;
+    let client = operator(&vco).await;
+    assert!(!client.uses_api_v2());
+
+    let enterprises = client.get_enterprises().await.unwrap();
+    assert_eq!(enterprises.len(), 2);
+    let acme = &enterprises[0];
+    assert_eq!(acme.id, EnterpriseId(12));
+    let edges = client.get_enterprise_edges(acme).await.unwrap();
+    assert_eq!(edges.len(), 2);
+    assert_eq!(edges[0].name, "branch-301");
+
+    client.delete_edge(acme, &edges[0]).await.unwrap();
+    assert_eq!(client.get_enterprise_edges(acme).await.unwrap().len(), 1);
+    assert_eq!(vco.calls("edge/deleteEdge"), 1);
+
+    // VCO says an edge that's gone changed nothing, rather than giving an error.
+    let error = client.delete_edge(acme, &edges[0]).await.unwrap_err();
+    assert!(error.is_not_found(), "{error:?}");
+}
+
+#[tokio::test]
+async fn test_api_v2() {
+    let vco = MockVco::start().await;
+    vco.set_api_v2(true);
+    vco.set_max_page_size(1);
+    let client = operator(&vco).await;
+    assert!(client.uses_api_v2());
+
+    // The same methods give the same things as with API v1, a page at a time.
+    let gateways = client.get_network_gateways().await.unwrap();
+    assert_eq!(gateways.len(), 2);
+    assert_eq!(gateways[0].name, "vcg01-lon");
+    assert_eq!(gateways[0].service_state, ServiceState::InService);
+    assert_eq!(gateways[0].enterprises, None);
+    assert_eq!(vco.calls("GET gateways"), 2);
+    assert_eq!(vco.calls("network/getNetworkGateways"), 0);
+
+    // Expansions need API v1.
+    let gateways = client
+        .get_network_gateways_with(&[GatewayExpansion::DataCenters])
+        .await
+        .unwrap();
+    assert_eq!(gateways.len(), 2);
+    assert_eq!(vco.calls("network/getNetworkGateways"), 1);
+    assert_eq!(vco.calls("GET gateways"), 2);
+
+    let enterprises = client.get_enterprises().await.unwrap();
+    assert_eq!(enterprises.len(), 2);
+    let acme = &enterprises[0];
+    assert_eq!(acme.id, EnterpriseId(12));
+    assert!(acme.alerts_enabled);
+    let edges = client.get_enterprise_edges(acme).await.unwrap();
+    assert_eq!(edges.len(), 2);
+    assert_eq!(edges[1].last_contact, None);
+    assert!(client
+        .get_enterprise_edges(&enterprises[1])
+        .await
+        .unwrap()
+        .is_empty());
+
+    client.delete_edge(acme, &edges[0]).await.unwrap();
+    let deleted = format!(
+        "DELETE enterprises/{}/edges/{}",
+        acme.logical_id, edges[0].logical_id
+    );
+    assert_eq!(vco.calls(&deleted), 1);
+    let error = client.delete_edge(acme, &edges[0]).await.unwrap_err();
+    assert!(error.is_not_found(), "{error:?}");
+    let edges = client.get_enterprise_edges(acme).await.unwrap();
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0].name, "branch-302");
+
+    // Retries and faults work as they do for API v1.
+    vco.fail_next("GET gateways", 1, Fault::status(503));
+    let client = client.with_retry_policy(RetryPolicy {
+        initial_backoff: Duration::from_millis(1),
+        ..RetryPolicy::default()
+    });
+    assert_eq!(client.get_network_gateways().await.unwrap().len(), 2);
+    assert_eq!(vco.calls("GET gateways"), 5);
+
+    // Enterprise admins can see their edges, but not the gateways.
+    let client = builder(&vco)
+        .login_enterprise_token(mock_vco::ENTERPRISE_TOKEN)
+        .await
+        .unwrap();
+    assert_eq!(client.get_enterprise_edges(acme).await.unwrap().len(), 1);
+    let error = client.get_network_gateways().await.unwrap_err();
+    assert!(
+        matches!(error, ClientError::OperatorRequired { .. }),
+        "{error:?}"
+    );
+    assert_eq!(vco.calls("GET gateways"), 5);
+}
+
+#[tokio::test]
+async fn test_api_v2_repeated_cursor() {
+    let vco = MockVco::start().await;
+    vco.set_api_v2(true);
+    vco.set_max_page_size(1);
+    vco.repeat_page_cursor();
+    let client = operator(&vco).await;
+
+    // Paging stops as soon as a cursor comes round again, rather than going on forever.
+    let error = client.get_network_gateways().await.unwrap_err();
+    assert!(matches!(error, ClientError::BadResponse(_)), "{error:?}");
+    assert_eq!(vco.calls("GET gateways"), 2);
+}
+
+#[tokio::test]
+async fn test_field_audit() {
+    let vco = MockVco::start().await;
+    let client = builder(&vco)
+        .field_audit(FieldAudit::Collect)
+        .login_password(mock_vco::OPERATOR_USERNAME, mock_vco::OPERATOR_PASSWORD)
+        .await
+        .unwrap();
+    client.get_network_gateways().await.unwrap();
+    client.get_system_properties().await.unwrap();
+    assert!(client.unhandled_field_report().is_empty());
+
+    // Pretend VCO has been upgraded, and now says more about its properties.
+    vco.set_fixture(
+        "systemProperty/getSystemProperties",
+        serde_json::json!([{
+            "id": 1,
+            "created": "2021-03-04T10:00:00.000Z",
+            "name": "product.version",
+            "value": "6.0.0",
+            "defaultValue": null,
+            "isReadOnly": 1,
+            "isPassword": 0,
+            "dataType": "STRING",
+            "description": "The VCO software version",
+            "modified": "2023-01-10T09:00:00.000Z",
+            "scope": "GLOBAL"
+        }]),
+    );
+    let properties = client.get_system_properties().await.unwrap();
+    assert_eq!(properties[0].value, PropertyValue::String("6.0.0".into()));
+    let report = client.unhandled_field_report();
+    assert_eq!(
+        report
+            .paths("systemProperty/getSystemProperties")
+            .collect::<Vec<_>>(),
+        vec!["[].scope"]
+    );
+
+    // API v2's responses are audited too, by verb and resource.
+    let mut gateways: serde_json::Value =
+        serde_json::from_str(include_str!("../../mock_vco/fixtures/v2_gateways.json")).unwrap();
+    gateways[0]["region"] = "eu-west".into();
+    vco.set_fixture("v2/gateways", gateways);
+    vco.set_api_v2(true);
+    let client = builder(&vco)
+        .field_audit(FieldAudit::Collect)
+        .login_password(mock_vco::OPERATOR_USERNAME, mock_vco::OPERATOR_PASSWORD)
+        .await
+        .unwrap();
+    client.get_network_gateways().await.unwrap();
+    let report = client.unhandled_field_report();
+    assert_eq!(
+        report.paths("GET gateways").collect::<Vec<_>>(),
+        vec!["data[].region"]
+    );
+}
+
+#[tokio::test]
+async fn test_deserialize_profile() {
+    let vco = MockVco::start().await;
+    // A VCO that sends booleans for tinyints, and a property type this crate doesn't know.
+    let properties = serde_json::json!([{
+        "id": 1,
+        "created": "2021-03-04T10:00:00.000Z",
+        "name": "product.version",
+        "value": "6.0.0",
+        "defaultValue": null,
+        "isReadOnly": true,
+        "isPassword": false,
+        "dataType": "SEMVER",
+        "description": "The VCO software version",
+        "modified": ""
+    }]);
+    vco.set_fixture("systemProperty/getSystemProperties", properties);
+
+    let client = operator(&vco).await;
+    let error = client.get_system_properties().await.unwrap_err();
+    assert!(matches!(error, ClientError::Json(_)), "{error:?}");
+
+    let client = client.with_deserialize_profile(DeserializeProfile::Lenient);
+    let properties = client.get_system_properties().await.unwrap();
+    assert!(properties[0].is_read_only);
+    assert_eq!(properties[0].value, PropertyValue::String("6.0.0".into()));
+    assert_eq!(properties[0].modified, None);
+}
+
+#[tokio::test]
+async fn test_version() {
+    let vco = MockVco::start().await;
+    let client = operator(&vco).await;
+    assert_eq!(client.version(), Some(VcoVersion::new(4, 5, 1)));
+
+    // Enterprise admins can't ask, so they have to say.
+    let client = builder(&vco)
+        .login_enterprise_password(mock_vco::ENTERPRISE_USERNAME, mock_vco::ENTERPRISE_PASSWORD)
+        .await
+        .unwrap();
+    assert_eq!(client.version(), None);
+
+    // Methods aren't checked against the version, so even an old one is refused nothing.
+    let client = builder(&vco)
+        .assume_version(VcoVersion::new(3, 4, 2))
+        .login_token(mock_vco::OPERATOR_TOKEN)
+        .await
+        .unwrap();
+    assert_eq!(client.version(), Some(VcoVersion::new(3, 4, 2)));
+    let start = time::OffsetDateTime::now_utc();
+    client
+        .get_gateway_status_metrics(GatewayId(80), start, None, &[Metric::CpuPct])
+        .await
+        .unwrap();
+    assert_eq!(vco.calls("metrics/getGatewayStatusMetrics"), 1);
+
+    // A release without a method says so, and the call fails as unsupported.
+    vco.fail_next(
+        "metrics/getGatewayStatusMetrics",
+        1,
+        Fault::api(-32601, "Method not found"),
+    );
+    let error = client
+        .get_gateway_status_metrics(GatewayId(80), start, None, &[Metric::CpuPct])
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            &error,
+            ClientError::Unsupported { method }
+                if method == "metrics/getGatewayStatusMetrics"
+        ),
+        "{error:?}"
+    );
+
+    // API v2 is used if VCO turns out to have it, whatever its version, unless told otherwise.
+    assert!(!client.uses_api_v2());
+    vco.set_api_v2(true);
+    assert!(operator(&vco).await.uses_api_v2());
+    // A probe that fails for some other reason than VCO not having API v2 doesn't mean it
+    // hasn't, but API v1 is used all the same.
+    vco.fail_next("GET enterprises", 1, Fault::status(500));
+    assert!(!operator(&vco).await.uses_api_v2());
+    let client = builder(&vco)
+        .assume_api_v2(false)
+        .login_token(mock_vco::OPERATOR_TOKEN)
+        .await
+        .unwrap();
+    assert!(!client.uses_api_v2());
+}
+
+#[tokio::test]
+async fn test_json_rpc_transport() {
+    let vco = MockVco::start().await;
+    let client = builder(&vco)
+        .transport(Transport::JsonRpc)
+        .login_token(mock_vco::OPERATOR_TOKEN)
+        .await
+        .unwrap();
+    assert_eq!(client.get_network_gateways().await.unwrap().len(), 2);
+
+    vco.fail_next(
+        "network/getNetworkGateways",
+        1,
+        Fault::api(-32000, "Something went wrong"),
+    );
+    let error = client.get_network_gateways().await.unwrap_err();
+    assert_eq!(error.api_error().unwrap().message, "Something went wrong");
+}
+
+#[tokio::test]
+async fn test_retry_transient_failures() {
+    let vco = MockVco::start().await;
+    let client = operator(&vco).await.with_retry_policy(RetryPolicy {
+        initial_backoff: Duration::from_millis(1),
+        ..RetryPolicy::default()
+    });
+
+    vco.fail_next("network/getNetworkGateways", 2, Fault::status(503));
+    assert_eq!(client.get_network_gateways().await.unwrap().len(), 2);
+    assert_eq!(vco.calls("network/getNetworkGateways"), 3);
+
+    // Non-transient errors aren't retried.
+    vco.fail_next(
+        "network/getNetworkGateways",
+        1,
+        Fault::api(-32602, "Invalid params"),
+    );
+    let error = client.get_network_gateways().await.unwrap_err();
+    assert!(!error.is_transient());
+    assert_eq!(vco.calls("network/getNetworkGateways"), 4);
+}
+
+#[tokio::test]
+async fn test_latency_and_timeout() {
+    let vco = MockVco::start().await;
+    let client = builder(&vco)
+        .timeout(Duration::from_millis(200))
+        .login_token(mock_vco::OPERATOR_TOKEN)
+        .await
+        .unwrap();
+
+    vco.set_latency(Duration::from_secs(2));
+    let error = client.get_network_gateways().await.unwrap_err();
+    assert!(matches!(error, ClientError::Request(_)), "{error:?}");
+    assert!(error.is_transient());
+}
+
+#[tokio::test]
+async fn test_expired_session() {
+    let vco = MockVco::start().await;
+    let client = operator(&vco).await;
+    vco.expire_sessions();
+    let error = client.get_network_gateways().await.unwrap_err();
+    assert!(error.is_session_expired(), "{error:?}");
+
+    // With a credential provider we log in again and replay the call.
+    let asked = Arc::new(AtomicUsize::new(0));
+    let provider = {
+        let asked = asked.clone();
+        CredentialProvider::new(move || {
+            asked.fetch_add(1, Ordering::SeqCst);
+            Some(Credentials::new(
+                mock_vco::OPERATOR_USERNAME,
+                mock_vco::OPERATOR_PASSWORD,
+            ))
+        })
+    };
+    let client = builder(&vco)
+        .credential_provider(provider)
+        .login_password(mock_vco::OPERATOR_USERNAME, mock_vco::OPERATOR_PASSWORD)
+        .await
+        .unwrap();
+    vco.expire_sessions();
+    assert_eq!(client.get_network_gateways().await.unwrap().len(), 2);
+    assert_eq!(asked.load(Ordering::SeqCst), 1);
+
+    // Concurrent calls finding the session expired only log in once between them.
+    vco.expire_sessions();
+    let (a, b) = tokio::join!(client.get_network_gateways(), client.get_network_gateways());
+    assert!(a.is_ok() && b.is_ok());
+    assert_eq!(asked.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_logout() {
+    let vco = MockVco::start().await;
+    let client = operator(&vco).await;
+    client.logout().await.unwrap();
+    assert!(client.session().is_none());
+    let error = client.get_network_gateways().await.unwrap_err();
+    assert!(error.is_session_expired());
+}
+
+#[tokio::test]
+async fn test_resume_session() {
+    let vco = MockVco::start().await;
+    let session = operator(&vco).await.session().unwrap();
+
+    let client = builder(&vco).resume_session(&session).await.unwrap();
+    assert_eq!(client.principal(), Some(Principal::Operator));
+    assert_eq!(client.get_network_gateways().await.unwrap().len(), 2);
+
+    vco.expire_sessions();
+    let error = builder(&vco).resume_session(&session).await.unwrap_err();
+    assert!(matches!(error, ClientError::AuthFailed(_)));
+}
+
+#[tokio::test]
+async fn test_record_and_replay() {
+    let path = std::env::temp_dir().join(format!(
+        "vco-rs-test-mock-cassette-{}.json",
+        std::process::id()
+    ));
+
+    // Record a session against the mock...
+    let vco = MockVco::start().await;
+    let client = builder(&vco)
+        .cassette(Cassette::record(&path))
+        .login_password(mock_vco::OPERATOR_USERNAME, mock_vco::OPERATOR_PASSWORD)
+        .await
+        .unwrap();
+    let gateways = client.get_network_gateways().await.unwrap();
+    let properties = client.get_system_properties().await.unwrap();
+    drop(vco);
+
+    // ...without writing any secrets down...
+    let recording = std::fs::read_to_string(&path).unwrap();
+    assert!(!recording.contains(mock_vco::OPERATOR_PASSWORD));
+    assert!(!recording.contains("smtp-s3cret"));
+
+    // ...then play it back with nothing listening.
+    let cassette = Cassette::replay(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let client = VcoClientBuilder::from_url(Url::parse("http://127.0.0.1:9/").unwrap())
+        .cassette(cassette.clone())
+        .login_password(mock_vco::OPERATOR_USERNAME, "any password")
+        .await
+        .unwrap();
+    assert_eq!(
+        client.identity().unwrap().username,
+        mock_vco::OPERATOR_USERNAME
+    );
+    let replayed = client.get_network_gateways().await.unwrap();
+    assert_eq!(replayed.len(), gateways.len());
+    assert_eq!(replayed[0].logical_id, gateways[0].logical_id);
+    let replayed = client.get_system_properties().await.unwrap();
+    assert_eq!(replayed.len(), properties.len());
+    assert_eq!(cassette.unplayed(), 0);
+
+    // Calls that weren't recorded fail loudly.
+    let error = client.get_network_gateways().await.unwrap_err();
+    assert!(
+        matches!(error, ClientError::CassetteUnmatched { .. }),
+        "{error:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_record_to_unwritable_cassette() {
+    // Somewhere that can't be written, since the directory doesn't exist.
+    let path = std::env::temp_dir()
+        .join(format!("vco-rs-test-missing-{}", std::process::id()))
+        .join("cassette.json");
+
+    // The calls still succeed; only the recording is lost.
+    let vco = MockVco::start().await;
+    let client = builder(&vco)
+        .cassette(Cassette::record(&path))
+        .login_password(mock_vco::OPERATOR_USERNAME, mock_vco::OPERATOR_PASSWORD)
+        .await
+        .unwrap();
+    assert!(!client.get_network_gateways().await.unwrap().is_empty());
+    assert!(!path.exists());
+}
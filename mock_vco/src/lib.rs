@@ -0,0 +1,1121 @@
+//! An in-process mock VCO, for testing `client` and tools built on it without a real
+//! orchestrator.
+//!
+//! `MockVco::start` serves the REST (`portal/rest/...`) and JSON-RPC (`portal/`) APIs on a random
+//! localhost port, answering from the fixtures in `fixtures/`. API v2 (`api/sdwan/v2/...`) is
+//! served too, with its collections paginated, once `MockVco::set_api_v2` says so. Knobs on
+//! `MockVco` inject faults, latency and expired sessions.
+//!
+//! ```no_run
+//! # async fn example() {
+//! use mock_vco::{Fault, MockVco};
+//!
+//! let vco = MockVco::start().await;
+//! vco.fail_next("network/getNetworkGateways", 1, Fault::status(503));
+//! // Point a client at `vco.base_url()` and log in with `mock_vco::OPERATOR_USERNAME`...
+//! # }
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::{Path, Query, State};
+use axum::http::header::{AUTHORIZATION, COOKIE, RETRY_AFTER, SET_COOKIE};
+use axum::http::{HeaderMap, HeaderValue, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{any, post};
+use axum::Router;
+use serde_json::{json, Value};
+use tokio::sync::oneshot;
+use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+/// The operator who can log in with a password.
+pub const OPERATOR_USERNAME: &str = "super@example.com";
+pub const OPERATOR_PASSWORD: &str = "vcadm!n-s3cret";
+/// The operator's API token.
+pub const OPERATOR_TOKEN: &str = "mock-operator-token";
+
+/// The enterprise admin who can log in with a password.
+pub const ENTERPRISE_USERNAME: &str = "admin@acme.example.com";
+pub const ENTERPRISE_PASSWORD: &str = "acme-s3cret";
+/// The enterprise admin's API token.
+pub const ENTERPRISE_TOKEN: &str = "mock-enterprise-token";
+
+/// The partner (MSP) admin who can log in with a password.
+pub const PARTNER_USERNAME: &str = "msp@partner.example.com";
+pub const PARTNER_PASSWORD: &str = "partner-s3cret";
+
+/// The name of the cookie sessions are kept in, as on a real VCO.
+pub const SESSION_COOKIE: &str = "velocloud.session";
+
+/// The self-signed certificate for `localhost` and `127.0.0.1` that `MockVco::start_tls` serves.
+pub const TLS_CERTIFICATE_PEM: &str = include_str!("../fixtures/tls_certificate.pem");
+/// The SHA-256 fingerprint of `TLS_CERTIFICATE_PEM`, as `openssl x509 -fingerprint -sha256`
+/// prints it.
+pub const TLS_CERTIFICATE_SHA256: &str =
+    "FC:68:4A:A7:9B:C7:26:7F:AB:C1:54:49:EF:56:E3:97:BE:76:9E:C6:81:0C:2E:BD:49:D3:36:36:F2:00:1B:04";
+const TLS_KEY_PEM: &str = include_str!("../fixtures/tls_key.pem");
+
+/// How long sessions last, in seconds.
+const SESSION_MAX_AGE: u64 = 1800;
+
+/// Fixture data, by the method that returns it. API v2 collections are under `v2/`, e.g.
+/// `v2/edges`. What a `with` expansion adds to a method's items is under `{method}:{expansion}`,
+/// each item having the ID of the one it belongs to.
+const FIXTURES: &[(&str, &str)] = &[
+    (
+        "network/getNetworkGateways",
+        include_str!("../fixtures/network_getNetworkGateways.json"),
+    ),
+    (
+        "network/getNetworkGateways:certificates",
+        include_str!("../fixtures/gateway_certificates.json"),
+    ),
+    (
+        "network/getNetworkEnterprises",
+        include_str!("../fixtures/network_getNetworkEnterprises.json"),
+    ),
+    (
+        "network/getNetworkGatewayPools",
+        include_str!("../fixtures/network_getNetworkGatewayPools.json"),
+    ),
+    (
+        "enterprise/getEnterpriseEdges",
+        include_str!("../fixtures/enterprise_getEnterpriseEdges.json"),
+    ),
+    (
+        "systemProperty/getSystemProperties",
+        include_str!("../fixtures/systemProperty_getSystemProperties.json"),
+    ),
+    (
+        "metrics/getGatewayStatusMetrics",
+        include_str!("../fixtures/metrics_getGatewayStatusMetrics.json"),
+    ),
+    (
+        "v2/enterprises",
+        include_str!("../fixtures/v2_enterprises.json"),
+    ),
+    ("v2/edges", include_str!("../fixtures/v2_edges.json")),
+    ("v2/gateways", include_str!("../fixtures/v2_gateways.json")),
+];
+
+/// The most items in a page of an API v2 collection, unless `MockVco::set_max_page_size` says
+/// otherwise.
+const MAX_PAGE_SIZE: usize = 100;
+
+/// Methods that only operators may call, by prefix.
+const OPERATOR_ONLY_PREFIXES: &[&str] = &[
+    "network/",
+    "systemProperty/",
+    "gateway/",
+    "operatorUser/",
+    "metrics/getGateway",
+];
+
+/// API v2 collections that only operators may use.
+const OPERATOR_ONLY_COLLECTIONS: &[&str] = &["gateways"];
+
+/// Something to go wrong with a call.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    /// Answer with a VCO API error.
+    Api { code: i32, message: String },
+    /// Answer with an HTTP error status and an empty body, with a `Retry-After` header if
+    /// `retry_after` seconds are given.
+    Status {
+        status: u16,
+        retry_after: Option<u64>,
+    },
+}
+
+impl Fault {
+    /// A VCO API error with `code` and `message`.
+    pub fn api(code: i32, message: &str) -> Self {
+        Fault::Api {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// An HTTP error `status`.
+    pub fn status(status: u16) -> Self {
+        Fault::Status {
+            status,
+            retry_after: None,
+        }
+    }
+}
+
+/// The kinds of user the mock knows about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Principal {
+    Operator,
+    Enterprise,
+    Partner,
+}
+
+/// Everything the mock remembers between calls.
+#[derive(Debug, Default)]
+struct MockState {
+    fixtures: HashMap<String, Value>,
+    faults: HashMap<String, VecDeque<Fault>>,
+    latency: Duration,
+    sessions: HashMap<String, Principal>,
+    next_session: u64,
+    calls: HashMap<String, usize>,
+    max_page_size: Option<usize>,
+    api_v2: bool,
+    repeat_page_cursor: bool,
+}
+
+type SharedState = Arc<Mutex<MockState>>;
+
+/// A running mock VCO. It stops when dropped.
+#[derive(Debug)]
+pub struct MockVco {
+    addr: SocketAddr,
+    tls: bool,
+    state: SharedState,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockVco {
+    /// Start serving on a random localhost port, on the current tokio runtime.
+    pub async fn start() -> Self {
+        Self::serve(None)
+    }
+
+    /// Like `start`, but serve HTTPS with `TLS_CERTIFICATE_PEM`.
+    pub async fn start_tls() -> Self {
+        let certificates = rustls_pemfile::certs(&mut TLS_CERTIFICATE_PEM.as_bytes())
+            .expect("Bad TLS certificate fixture")
+            .into_iter()
+            .map(Certificate)
+            .collect();
+        let key = rustls_pemfile::pkcs8_private_keys(&mut TLS_KEY_PEM.as_bytes())
+            .expect("Bad TLS key fixture")
+            .pop()
+            .map(PrivateKey)
+            .expect("TLS key fixture has a key");
+        let config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(certificates, key)
+            .expect("TLS key fixture matches the certificate");
+        Self::serve(Some(TlsAcceptor::from(Arc::new(config))))
+    }
+
+    /// Serve on a random localhost port, over TLS if there's an `acceptor`.
+    fn serve(acceptor: Option<TlsAcceptor>) -> Self {
+        let fixtures = FIXTURES
+            .iter()
+            .map(|(method, text)| {
+                let value = serde_json::from_str(text)
+                    .unwrap_or_else(|e| panic!("Bad fixture for {method}: {e}"));
+                (method.to_string(), value)
+            })
+            .collect();
+        let state = Arc::new(Mutex::new(MockState {
+            fixtures,
+            ..Default::default()
+        }));
+
+        let app = Router::new()
+            .route("/portal/rest/*method", post(rest))
+            .route("/portal/", post(json_rpc))
+            .route("/api/sdwan/v2/*resource", any(rest_v2))
+            .with_state(state.clone());
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Couldn't bind a local port");
+        let addr = listener.local_addr().expect("Bound socket has an address");
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        let tls = acceptor.is_some();
+        if let Some(acceptor) = acceptor {
+            listener
+                .set_nonblocking(true)
+                .expect("Couldn't serve on the bound port");
+            let listener = tokio::net::TcpListener::from_std(listener)
+                .expect("Couldn't serve on the bound port");
+            tokio::spawn(serve_tls(listener, acceptor, app, shutdown_rx));
+        } else {
+            let server = axum::Server::from_tcp(listener)
+                .expect("Couldn't serve on the bound port")
+                .serve(app.into_make_service())
+                .with_graceful_shutdown(async {
+                    shutdown_rx.await.ok();
+                });
+            tokio::spawn(server);
+        }
+
+        Self {
+            addr,
+            tls,
+            state,
+            shutdown: Some(shutdown),
+        }
+    }
+
+    /// The base URL to point a client at, e.g. `http://127.0.0.1:43210/`, or `https://...` if
+    /// started with `start_tls`.
+    pub fn base_url(&self) -> String {
+        let scheme = if self.tls { "https" } else { "http" };
+        format!("{scheme}://{}/", self.addr)
+    }
+
+    /// Answer `method` with `result` from now on, instead of its fixture. For API v2, `method` is
+    /// the fixture's name, e.g. `v2/gateways`.
+    pub fn set_fixture(&self, method: &str, result: Value) {
+        self.state().fixtures.insert(method.into(), result);
+    }
+
+    /// Answer the next `times` calls to `method` with `fault`, after any faults already queued.
+    /// API v2 calls are named by verb and resource, without the query, e.g. `GET gateways`.
+    pub fn fail_next(&self, method: &str, times: usize, fault: Fault) {
+        self.state()
+            .faults
+            .entry(method.into())
+            .or_default()
+            .extend(std::iter::repeat_n(fault, times));
+    }
+
+    /// Wait `latency` before answering each call.
+    pub fn set_latency(&self, latency: Duration) {
+        self.state().latency = latency;
+    }
+
+    /// End every password session, as VCO does when they time out. API tokens still work.
+    pub fn expire_sessions(&self) {
+        self.state().sessions.clear();
+    }
+
+    /// How many times `method` has been called, including calls that failed. API v2 calls are
+    /// named as for `fail_next`.
+    pub fn calls(&self, method: &str) -> usize {
+        self.state().calls.get(method).copied().unwrap_or_default()
+    }
+
+    /// Claim to be release `version` of VCO, e.g. "5.2.0", in the `product.version` property.
+    pub fn set_version(&self, version: &str) {
+        let mut state = self.state();
+        let property = find_property(&mut state, Some("product.version"))
+            .expect("Properties fixture has product.version");
+        property["value"] = json!(version);
+    }
+
+    /// Return at most `size` items in each page of an API v2 collection, whatever limit the
+    /// client asks for.
+    pub fn set_max_page_size(&self, size: usize) {
+        self.state().max_page_size = Some(size);
+    }
+
+    /// Give the same cursor for every page of an API v2 collection, and always say there are
+    /// more, as a broken server might.
+    pub fn repeat_page_cursor(&self) {
+        self.state().repeat_page_cursor = true;
+    }
+
+    /// Act as a release with API v2, or without it, as it does to begin with. Without it, every
+    /// `api/sdwan/v2` call gets 404 Not Found.
+    pub fn set_api_v2(&self, available: bool) {
+        self.state().api_v2 = available;
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap()
+    }
+}
+
+impl Drop for MockVco {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
+
+/// Serve `app` over TLS to each connection `listener` accepts, until `shutdown` fires.
+async fn serve_tls(
+    listener: tokio::net::TcpListener,
+    acceptor: TlsAcceptor,
+    app: Router,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    loop {
+        let stream = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(_) => continue,
+            },
+            _ = &mut shutdown => return,
+        };
+        let acceptor = acceptor.clone();
+        let app = app.clone();
+        tokio::spawn(async move {
+            // Clients that reject the certificate abandon the handshake, which is fine.
+            if let Ok(stream) = acceptor.accept(stream).await {
+                hyper::server::conn::Http::new()
+                    .serve_connection(stream, app)
+                    .await
+                    .ok();
+            }
+        });
+    }
+}
+
+//
+// REQUEST HANDLING
+//
+
+/// The outcome of a call, before it's put in the transport's envelope.
+enum Reply {
+    Result {
+        result: Value,
+        set_cookie: Option<String>,
+    },
+    Error {
+        code: i32,
+        message: String,
+    },
+    Status {
+        status: StatusCode,
+        retry_after: Option<u64>,
+    },
+}
+
+impl Reply {
+    fn result(result: Value) -> Self {
+        Reply::Result {
+            result,
+            set_cookie: None,
+        }
+    }
+
+    fn error(code: i32, message: &str) -> Self {
+        Reply::Error {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<Fault> for Reply {
+    fn from(fault: Fault) -> Self {
+        match fault {
+            Fault::Api { code, message } => Reply::Error { code, message },
+            Fault::Status {
+                status,
+                retry_after,
+            } => Reply::Status {
+                status: StatusCode::from_u16(status).expect("Fault has a valid HTTP status"),
+                retry_after,
+            },
+        }
+    }
+}
+
+/// `POST portal/rest/{method}`
+async fn rest(
+    State(state): State<SharedState>,
+    Path(method): Path<String>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    let params = parse_params(&body);
+    rest_response(handle(&state, &method, &headers, params).await)
+}
+
+/// Put `reply` in a REST response.
+fn rest_response(reply: Reply) -> Response {
+    match reply {
+        Reply::Result { result, set_cookie } => {
+            let mut response = axum::Json(result).into_response();
+            add_cookie(&mut response, set_cookie);
+            response
+        }
+        Reply::Error { code, message } => (
+            StatusCode::BAD_REQUEST,
+            axum::Json(json!({"error": {"code": code, "message": message}})),
+        )
+            .into_response(),
+        Reply::Status {
+            status,
+            retry_after,
+        } => status_response(status, retry_after),
+    }
+}
+
+/// `POST portal/`
+async fn json_rpc(State(state): State<SharedState>, headers: HeaderMap, body: String) -> Response {
+    let request: Value = serde_json::from_str(&body).unwrap_or_default();
+    let id = request.get("id").cloned().unwrap_or_default();
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return axum::Json(json!({
+            "jsonrpc": "2.0",
+            "error": {"code": -32600, "message": "Invalid Request"},
+            "id": id,
+        }))
+        .into_response();
+    };
+    let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+    match handle(&state, method, &headers, params).await {
+        Reply::Result { result, set_cookie } => {
+            let mut response =
+                axum::Json(json!({"jsonrpc": "2.0", "result": result, "id": id})).into_response();
+            add_cookie(&mut response, set_cookie);
+            response
+        }
+        Reply::Error { code, message } => axum::Json(json!({
+            "jsonrpc": "2.0",
+            "error": {"code": code, "message": message},
+            "id": id,
+        }))
+        .into_response(),
+        Reply::Status {
+            status,
+            retry_after,
+        } => status_response(status, retry_after),
+    }
+}
+
+fn parse_params(body: &str) -> Value {
+    if body.trim().is_empty() {
+        json!({})
+    } else {
+        serde_json::from_str(body).unwrap_or_default()
+    }
+}
+
+fn add_cookie(response: &mut Response, set_cookie: Option<String>) {
+    if let Some(cookie) = set_cookie {
+        response.headers_mut().insert(
+            SET_COOKIE,
+            HeaderValue::from_str(&cookie).expect("Cookie is a valid header"),
+        );
+    }
+}
+
+fn status_response(status: StatusCode, retry_after: Option<u64>) -> Response {
+    let mut response = status.into_response();
+    if let Some(seconds) = retry_after {
+        response
+            .headers_mut()
+            .insert(RETRY_AFTER, HeaderValue::from(seconds));
+    }
+    response
+}
+
+/// Count a call to `method` and wait out the latency, then give the fault to answer it with, if
+/// one is queued.
+async fn start_call(state: &SharedState, method: &str) -> Option<Fault> {
+    let latency = {
+        let mut state = state.lock().unwrap();
+        *state.calls.entry(method.into()).or_default() += 1;
+        state.latency
+    };
+    if !latency.is_zero() {
+        tokio::time::sleep(latency).await;
+    }
+    let mut state = state.lock().unwrap();
+    state.faults.get_mut(method).and_then(VecDeque::pop_front)
+}
+
+/// Answer a call to `method`, whichever transport it came in on.
+async fn handle(state: &SharedState, method: &str, headers: &HeaderMap, params: Value) -> Reply {
+    if let Some(fault) = start_call(state, method).await {
+        return fault.into();
+    }
+
+    let mut state = state.lock().unwrap();
+
+    match method {
+        "login/operatorLogin" => return login(&mut state, &params, &[Principal::Operator]),
+        "login/enterpriseLogin" => {
+            return login(
+                &mut state,
+                &params,
+                &[Principal::Enterprise, Principal::Partner],
+            )
+        }
+        "logout" => {
+            if let Some(session) = session_cookie(headers) {
+                state.sessions.remove(&session);
+            }
+            return Reply::Result {
+                result: Value::Null,
+                set_cookie: Some(format!("{SESSION_COOKIE}=; Path=/; Max-Age=0")),
+            };
+        }
+        _ => {}
+    }
+
+    let Some(principal) = authenticate(&state, headers) else {
+        return Reply::error(-32000, "tokenError [expired session cookie]");
+    };
+    if principal != Principal::Operator
+        && OPERATOR_ONLY_PREFIXES
+            .iter()
+            .any(|prefix| method.starts_with(prefix))
+    {
+        return Reply::error(-32603, "Privilege denied for this operation");
+    }
+
+    match method {
+        "operatorUser/getOperatorUser" => Reply::result(user(Principal::Operator)),
+        "enterpriseUser/getEnterpriseUser" if principal == Principal::Enterprise => {
+            Reply::result(user(principal))
+        }
+        "enterpriseProxy/getEnterpriseProxyUser" if principal == Principal::Partner => {
+            Reply::result(user(principal))
+        }
+        "enterpriseUser/getEnterpriseUser" | "enterpriseProxy/getEnterpriseProxyUser" => {
+            Reply::error(-32603, "Privilege denied for this operation")
+        }
+        "systemProperty/getSystemProperty" => {
+            let name = params.get("name").and_then(Value::as_str);
+            match find_property(&mut state, name) {
+                Some(property) => Reply::result(property.clone()),
+                None => Reply::error(-32000, "systemProperty not found"),
+            }
+        }
+        "systemProperty/updateSystemProperty" => {
+            let name = params.get("name").and_then(Value::as_str);
+            let update = params.get("_update").cloned().unwrap_or_default();
+            match find_property(&mut state, name) {
+                Some(property) => {
+                    if let (Value::Object(property), Value::Object(update)) = (property, update) {
+                        property.extend(update);
+                    }
+                    Reply::result(json!({"rows": 1}))
+                }
+                None => Reply::error(-32000, "systemProperty not found"),
+            }
+        }
+        "network/getNetworkGateways" => Reply::result(expand(&state, method, "gatewayId", &params)),
+        "metrics/getGatewayStatusMetrics" => {
+            // Only the metrics asked for, as VCO does.
+            let asked = params["metrics"].as_array().cloned().unwrap_or_default();
+            let metrics = state.fixtures[method]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter(|(name, _)| asked.iter().any(|metric| metric == *name))
+                .map(|(name, values)| (name.clone(), values.clone()))
+                .collect();
+            Reply::result(Value::Object(metrics))
+        }
+        "gateway/gatewayProvision" => provision_gateway(&mut state, &params),
+        "gateway/updateGatewayAttributes" => {
+            let mut update = params.clone();
+            let id = update
+                .as_object_mut()
+                .and_then(|update| update.remove("id"))
+                .unwrap_or_default();
+            match find_gateway(&mut state, &id) {
+                Some(gateway) => {
+                    if let (Value::Object(gateway), Value::Object(update)) = (gateway, update) {
+                        gateway.extend(update);
+                    }
+                    Reply::result(json!({"rows": 1}))
+                }
+                // VCO changes nothing rather than giving an error.
+                None => Reply::result(json!({"rows": 0})),
+            }
+        }
+        "gateway/deleteGateway" => {
+            let id = params.get("id").cloned().unwrap_or_default();
+            if find_gateway(&mut state, &id).is_none() {
+                return Reply::result(json!({"rows": 0}));
+            }
+            gateways(&mut state).retain(|gateway| gateway["id"] != id);
+            Reply::result(json!({"rows": 1}))
+        }
+        "network/insertNetworkGatewayPool" => insert_pool(&mut state, &params),
+        "network/updateNetworkGatewayPoolAttributes" => {
+            let id = params.get("id").cloned().unwrap_or_default();
+            let update = params.get("_update").cloned().unwrap_or_default();
+            update_item(&mut state, "network/getNetworkGatewayPools", &id, update)
+        }
+        "network/deleteNetworkGatewayPool" => {
+            let id = params.get("id").cloned().unwrap_or_default();
+            let in_use = items(&mut state, "network/getNetworkEnterprises")
+                .iter()
+                .any(|enterprise| enterprise["gatewayPoolId"] == id);
+            if in_use {
+                return Reply::error(-32000, "gateway pool has enterprises assigned");
+            }
+            let pools = items(&mut state, "network/getNetworkGatewayPools");
+            let count = pools.len();
+            pools.retain(|pool| pool["id"] != id);
+            Reply::result(json!({"rows": count - pools.len()}))
+        }
+        "network/insertNetworkGatewayPoolGateway" => add_pool_member(&mut state, &params),
+        "network/deleteNetworkGatewayPoolGateway" => {
+            // By the membership's ID, not the pool's and gateway's.
+            let id = params.get("id").cloned().unwrap_or_default();
+            let mut rows = 0;
+            for pool in items(&mut state, "network/getNetworkGatewayPools") {
+                if let Some(members) = pool["gateways"].as_array_mut() {
+                    let count = members.len();
+                    members.retain(|member| member["gatewayPoolAssocId"] != id);
+                    rows += count - members.len();
+                }
+            }
+            Reply::result(json!({"rows": rows}))
+        }
+        "enterprise/updateEnterprise" => {
+            let id = params.get("id").cloned().unwrap_or_default();
+            let update = params.get("_update").cloned().unwrap_or_default();
+            update_item(&mut state, "network/getNetworkEnterprises", &id, update)
+        }
+        "edge/deleteEdge" => {
+            let id = params.get("id").cloned().unwrap_or_default();
+            let edges = state
+                .fixtures
+                .get_mut("enterprise/getEnterpriseEdges")
+                .and_then(Value::as_array_mut);
+            match edges {
+                Some(edges) if edges.iter().any(|edge| edge["id"] == id) => {
+                    edges.retain(|edge| edge["id"] != id);
+                    Reply::result(json!([{"id": id, "rows": 1}]))
+                }
+                // Like VCO, deleting an edge that isn't there changes nothing.
+                _ => Reply::result(json!([{"id": id, "rows": 0}])),
+            }
+        }
+        _ => match state.fixtures.get(method) {
+            Some(result) => Reply::result(result.clone()),
+            None => Reply::error(-32601, "Method not found"),
+        },
+    }
+}
+
+/// `GET`, `PUT` or `DELETE api/sdwan/v2/{resource}`
+async fn rest_v2(
+    State(state): State<SharedState>,
+    verb: Method,
+    Path(resource): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    if let Some(fault) = start_call(&state, &format!("{verb} {resource}")).await {
+        return rest_response(fault.into());
+    }
+
+    let mut state = state.lock().unwrap();
+    if !state.api_v2 {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let Some(principal) = authenticate(&state, &headers) else {
+        return v2_error(StatusCode::UNAUTHORIZED, "Authentication required");
+    };
+    let collection = resource.split('/').next().unwrap_or_default();
+    if principal != Principal::Operator && OPERATOR_ONLY_COLLECTIONS.contains(&collection) {
+        return v2_error(StatusCode::FORBIDDEN, "Privilege denied for this operation");
+    }
+    let Some(Target {
+        fixture,
+        in_scope,
+        item,
+    }) = resolve(&state, &resource)
+    else {
+        return v2_error(StatusCode::NOT_FOUND, "Resource not found");
+    };
+    let max_page_size = state.max_page_size.unwrap_or(MAX_PAGE_SIZE);
+    let repeat_page_cursor = state.repeat_page_cursor;
+    let items = state
+        .fixtures
+        .get_mut(&fixture)
+        .and_then(Value::as_array_mut)
+        .expect("API v2 fixtures are arrays");
+
+    match (verb, item) {
+        (Method::GET, Some(index)) => axum::Json(items[index].clone()).into_response(),
+        (Method::GET, None) => {
+            let limit = query
+                .get("limit")
+                .and_then(|limit| limit.parse().ok())
+                .unwrap_or(max_page_size)
+                .min(max_page_size);
+            // The cursor is just the offset, but clients shouldn't rely on that.
+            let offset: usize = query
+                .get("nextPageLink")
+                .and_then(|cursor| cursor.parse().ok())
+                .unwrap_or(0);
+            let end = in_scope.len().min(offset + limit);
+            let data: Vec<Value> = in_scope
+                .get(offset..end)
+                .unwrap_or_default()
+                .iter()
+                .map(|&index| items[index].clone())
+                .collect();
+            let more = end < in_scope.len() || repeat_page_cursor;
+            let end = if repeat_page_cursor { limit } else { end };
+            axum::Json(json!({
+                "_metadata": {
+                    "limit": limit,
+                    "more": more,
+                    "nextPageLink": more.then(|| end.to_string()),
+                },
+                "data": data,
+            }))
+            .into_response()
+        }
+        (Method::PUT, Some(index)) => {
+            if let (Value::Object(item), Value::Object(update)) =
+                (&mut items[index], parse_params(&body))
+            {
+                item.extend(update);
+            }
+            axum::Json(items[index].clone()).into_response()
+        }
+        (Method::DELETE, Some(index)) => {
+            items.remove(index);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        _ => v2_error(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed"),
+    }
+}
+
+/// An API v2 error response.
+fn v2_error(status: StatusCode, message: &str) -> Response {
+    let body = json!({"code": status.as_u16(), "message": message});
+    (status, axum::Json(body)).into_response()
+}
+
+/// What an API v2 resource path refers to in the fixtures.
+struct Target {
+    /// The fixture holding the collection.
+    fixture: String,
+    /// The indexes in the fixture of the collection's items. A nested collection only has its
+    /// parent's children, e.g. `enterprises/{logicalId}/edges` has the edges with the
+    /// enterprise's ID as their `enterpriseId`.
+    in_scope: Vec<usize>,
+    /// The index of the item the path ends with, if it ends with one.
+    item: Option<usize>,
+}
+
+/// Find what `resource`, e.g. `enterprises/{logicalId}/edges`, refers to, if it exists.
+fn resolve(state: &MockState, resource: &str) -> Option<Target> {
+    let segments: Vec<&str> = resource.split('/').collect();
+    let mut parent: Option<(&str, &Value)> = None;
+    let mut target = None;
+    for pair in segments.chunks(2) {
+        let fixture = format!("v2/{}", pair[0]);
+        let items = state.fixtures.get(&fixture)?.as_array()?;
+        let in_scope: Vec<usize> = (0..items.len())
+            .filter(|&index| match parent {
+                Some((collection, parent)) => {
+                    let key = format!("{}Id", collection.trim_end_matches('s'));
+                    items[index].get(&key) == parent.get("id")
+                }
+                None => true,
+            })
+            .collect();
+        let item = match pair.get(1) {
+            Some(&logical_id) => Some(
+                *in_scope
+                    .iter()
+                    .find(|&&index| items[index]["logicalId"] == logical_id)?,
+            ),
+            None => None,
+        };
+        if let Some(index) = item {
+            parent = Some((pair[0], &items[index]));
+        }
+        target = Some(Target {
+            fixture,
+            in_scope,
+            item,
+        });
+    }
+    target
+}
+
+/// Check the credentials in `params`, starting a session if they're for one of `principals`.
+fn login(state: &mut MockState, params: &Value, principals: &[Principal]) -> Reply {
+    let username = params.get("username").and_then(Value::as_str);
+    let password = params.get("password").and_then(Value::as_str);
+    let principal = match (username, password) {
+        (Some(OPERATOR_USERNAME), Some(OPERATOR_PASSWORD)) => Principal::Operator,
+        (Some(ENTERPRISE_USERNAME), Some(ENTERPRISE_PASSWORD)) => Principal::Enterprise,
+        (Some(PARTNER_USERNAME), Some(PARTNER_PASSWORD)) => Principal::Partner,
+        _ => return Reply::error(-32000, "authenticationError [invalid username or password]"),
+    };
+    if !principals.contains(&principal) {
+        return Reply::error(-32000, "authenticationError [invalid username or password]");
+    }
+
+    state.next_session += 1;
+    let session = format!("mock-session-{}", state.next_session);
+    state.sessions.insert(session.clone(), principal);
+    Reply::Result {
+        result: Value::Null,
+        set_cookie: Some(format!(
+            "{SESSION_COOKIE}={session}; Path=/; Max-Age={SESSION_MAX_AGE}; HttpOnly"
+        )),
+    }
+}
+
+/// Work out who's calling from their API token or session cookie.
+fn authenticate(state: &MockState, headers: &HeaderMap) -> Option<Principal> {
+    if let Some(token) = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Token "))
+    {
+        return match token {
+            OPERATOR_TOKEN => Some(Principal::Operator),
+            ENTERPRISE_TOKEN => Some(Principal::Enterprise),
+            _ => None,
+        };
+    }
+    session_cookie(headers).and_then(|session| state.sessions.get(&session).copied())
+}
+
+fn session_cookie(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|cookie| {
+            cookie
+                .trim()
+                .strip_prefix(SESSION_COOKIE)
+                .and_then(|rest| rest.strip_prefix('='))
+                .map(String::from)
+        })
+}
+
+/// The user record for `principal`.
+fn user(principal: Principal) -> Value {
+    match principal {
+        Principal::Operator => json!({
+            "id": 1,
+            "username": OPERATOR_USERNAME,
+            "userType": "OPERATOR",
+            "roleId": 1,
+            "roleName": "Superuser",
+            "networkId": 1,
+            "isActive": 1,
+            "isLocked": 0,
+        }),
+        Principal::Enterprise => json!({
+            "id": 20,
+            "username": ENTERPRISE_USERNAME,
+            "userType": "ENTERPRISE",
+            "roleId": 3,
+            "roleName": "Enterprise Superuser",
+            "enterpriseId": 12,
+            "enterpriseProxyId": 3,
+            "isActive": 1,
+            "isLocked": 0,
+        }),
+        Principal::Partner => json!({
+            "id": 30,
+            "username": PARTNER_USERNAME,
+            "userType": "MSP",
+            "roleId": 5,
+            "roleName": "MSP Superuser",
+            "enterpriseProxyId": 3,
+            "isActive": 1,
+            "isLocked": 0,
+        }),
+    }
+}
+
+/// The gateways in the `getNetworkGateways` fixture.
+fn gateways(state: &mut MockState) -> &mut Vec<Value> {
+    items(state, "network/getNetworkGateways")
+}
+
+/// The items in the fixture for `method`, with what each expansion in `params`'s `with` adds to
+/// them. An expansion's items belong to the item whose ID is in their `id_field`. Expansions
+/// without a fixture are left out.
+fn expand(state: &MockState, method: &str, id_field: &str, params: &Value) -> Value {
+    let mut result = state.fixtures[method].clone();
+    let expansions = params["with"].as_array().cloned().unwrap_or_default();
+    for expansion in expansions.iter().filter_map(Value::as_str) {
+        let Some(Value::Array(extras)) = state.fixtures.get(&format!("{method}:{expansion}"))
+        else {
+            continue;
+        };
+        for item in result.as_array_mut().into_iter().flatten() {
+            let belonging = extras
+                .iter()
+                .filter(|extra| extra[id_field] == item["id"])
+                .cloned()
+                .collect();
+            item[expansion] = Value::Array(belonging);
+        }
+    }
+    result
+}
+
+/// The items in the fixture for `method`, which returns an array.
+fn items<'a>(state: &'a mut MockState, method: &str) -> &'a mut Vec<Value> {
+    state
+        .fixtures
+        .get_mut(method)
+        .and_then(Value::as_array_mut)
+        .unwrap_or_else(|| panic!("Fixture for {method} is an array"))
+}
+
+/// Merge `update` into the item with ID `id` in the fixture for `method`.
+fn update_item(state: &mut MockState, method: &str, id: &Value, update: Value) -> Reply {
+    match items(state, method)
+        .iter_mut()
+        .find(|item| item["id"] == *id)
+    {
+        Some(Value::Object(item)) => {
+            if let Value::Object(update) = update {
+                item.extend(update);
+            }
+            Reply::result(json!({"rows": 1}))
+        }
+        _ => Reply::result(json!({"rows": 0})),
+    }
+}
+
+/// Add a gateway pool, with no gateways, to the `getNetworkGatewayPools` fixture.
+fn insert_pool(state: &mut MockState, params: &Value) -> Reply {
+    let Some(name) = params.get("name") else {
+        return Reply::error(-32602, "name is required");
+    };
+    let pools = items(state, "network/getNetworkGatewayPools");
+    let id = pools
+        .iter()
+        .filter_map(|pool| pool["id"].as_i64())
+        .max()
+        .unwrap_or_default()
+        + 1;
+    pools.push(json!({
+        "id": id,
+        "networkId": params.get("networkId").cloned().unwrap_or(json!(1)),
+        "enterpriseProxyId": null,
+        "created": "2023-06-18T12:00:00.000Z",
+        "name": name,
+        "description": params.get("description").cloned().unwrap_or_default(),
+        "logicalId": format!("00000000-0000-4000-8000-{id:012}"),
+        "isDefault": 0,
+        "ipV4Enabled": 1,
+        "ipV6Enabled": 0,
+        "handOffType": params.get("handOffType").cloned().unwrap_or(json!("NONE")),
+        "modified": "2023-06-18T12:00:00.000Z",
+        "gateways": [],
+    }));
+    Reply::result(json!({"id": id}))
+}
+
+/// Add a gateway to a pool in the `getNetworkGatewayPools` fixture.
+fn add_pool_member(state: &mut MockState, params: &Value) -> Reply {
+    let pool_id = params.get("gatewayPoolId").cloned().unwrap_or_default();
+    let gateway_id = params.get("gatewayId").cloned().unwrap_or_default();
+    let Some(gateway) = find_gateway(state, &gateway_id).cloned() else {
+        return Reply::error(-32000, "gateway not found");
+    };
+    let pools = items(state, "network/getNetworkGatewayPools");
+    let next_assoc_id = pools
+        .iter()
+        .flat_map(|pool| pool["gateways"].as_array().into_iter().flatten())
+        .filter_map(|member| member["gatewayPoolAssocId"].as_i64())
+        .max()
+        .unwrap_or(100)
+        + 1;
+    let Some(members) = pools
+        .iter_mut()
+        .find(|pool| pool["id"] == pool_id)
+        .and_then(|pool| pool["gateways"].as_array_mut())
+    else {
+        return Reply::error(-32000, "gateway pool not found");
+    };
+    if members.iter().any(|member| member["id"] == gateway_id) {
+        return Reply::error(-32000, "gateway is already in the pool");
+    }
+    members.push(json!({
+        "id": gateway_id,
+        "name": gateway["name"],
+        "logicalId": gateway["logicalId"],
+        "gatewayPoolAssocId": next_assoc_id,
+    }));
+    Reply::result(json!({"rows": 1}))
+}
+
+/// The gateway with ID `id`, from the `getNetworkGateways` fixture.
+fn find_gateway<'a>(state: &'a mut MockState, id: &Value) -> Option<&'a mut Value> {
+    gateways(state)
+        .iter_mut()
+        .find(|gateway| gateway["id"] == *id)
+}
+
+/// Add a new, never activated gateway to the `getNetworkGateways` fixture.
+fn provision_gateway(state: &mut MockState, params: &Value) -> Reply {
+    let (Some(name), Some(ip_address)) = (params.get("name"), params.get("ipAddress")) else {
+        return Reply::error(-32602, "name and ipAddress are required");
+    };
+    let gateways = gateways(state);
+    let id = gateways
+        .iter()
+        .filter_map(|gateway| gateway["id"].as_i64())
+        .max()
+        .unwrap_or_default()
+        + 1;
+    let logical_id = format!("gateway00000000-0000-4000-8000-{id:012}");
+    let activation_key = format!("MOCK-{id:04}-ACTV-KEY0");
+    gateways.push(json!({
+        "id": id,
+        "created": "2023-06-18T12:00:00.000Z",
+        "networkId": params.get("networkId").cloned().unwrap_or(json!(1)),
+        "enterpriseProxyId": null,
+        "siteId": 100 + id,
+        "activationKey": activation_key,
+        "activationState": "PENDING",
+        "activationTime": "0000-00-00 00:00:00",
+        "softwareVersion": "4.5.1",
+        "buildNumber": "R451-20221130-GA",
+        "utilization": 0,
+        "utilizationDetail": null,
+        "connectedEdges": 0,
+        "connectedEdgeList": null,
+        "deviceId": null,
+        "logicalId": logical_id,
+        "name": name,
+        "dnsName": null,
+        "description": params.get("description").cloned().unwrap_or_default(),
+        "isLoadBalanced": 0,
+        "privateIpAddress": null,
+        "ipAddress": ip_address,
+        "ipV6Address": null,
+        "lastContact": "0000-00-00 00:00:00",
+        "systemUpSince": "0000-00-00 00:00:00",
+        "serviceUpSince": "0000-00-00 00:00:00",
+        "serviceState": "OUT_OF_SERVICE",
+        "gatewayState": "NEVER_ACTIVATED",
+        "alertsEnabled": 0,
+        "handOffDetail": null,
+        "ipsecGatewayDetail": null,
+        "bastionState": "UNCONFIGURED",
+        "endpointPkiMode": "CERTIFICATE_DISABLED",
+        "modified": "2023-06-18T12:00:00.000Z"
+    }));
+    Reply::result(json!({
+        "id": id,
+        "logicalId": logical_id,
+        "activationKey": activation_key,
+    }))
+}
+
+/// The system property called `name`, from the `getSystemProperties` fixture.
+fn find_property<'a>(state: &'a mut MockState, name: Option<&str>) -> Option<&'a mut Value> {
+    let name = name?;
+    state
+        .fixtures
+        .get_mut("systemProperty/getSystemProperties")?
+        .as_array_mut()?
+        .iter_mut()
+        .find(|property| property.get("name").and_then(Value::as_str) == Some(name))
+}